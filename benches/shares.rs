@@ -0,0 +1,65 @@
+//! How long a frame of the whole interface takes as sessions grow. Once
+//! worked out the shares, balances and the rows the lists show are only
+//! looked up, so `frame` should take about the same time at every size
+//! while `recompute` grows with the session.
+
+use criterion::{black_box, criterion_group, criterion_main, BenchmarkId, Criterion};
+use imgui_test::{HeadlessApp, Participant, Receipt, State};
+
+const PARTICIPANTS: usize = 20;
+const SIZES: [usize; 3] = [100, 1000, 5000];
+
+/// A session of `receipts` receipts shared by everyone, every fifth paid
+/// for unevenly so that it brings excluded items along.
+fn session(receipts: usize) -> State {
+    let mut state = State::default();
+    for i in 0..PARTICIPANTS {
+        state.add_participant(Participant::new(format!("Participant {i}")));
+    }
+    let ids: Vec<_> = state.participants.iter().map(|p| p.id).collect();
+
+    for i in 0..receipts {
+        let cents = 1000 + (i as i64 * 37) % 9000;
+        let owed: Vec<_> = ids
+            .iter()
+            .enumerate()
+            .map(|(n, id)| {
+                let extra = if i % 5 == 0 { n as i64 * 10 } else { 0 };
+                (*id, cents + extra)
+            })
+            .collect();
+        let total = owed.iter().map(|(_, c)| c).sum::<i64>() as f32 / 100.;
+        let mut receipt = Receipt::new(format!("Receipt {i}"), total, false);
+        receipt.payer = Some(ids[i % PARTICIPANTS]);
+        state.add_split(receipt, &owed);
+    }
+    state
+}
+
+fn shares(c: &mut Criterion) {
+    let mut group = c.benchmark_group("frame");
+    for size in SIZES {
+        let mut app = HeadlessApp::new(session(size));
+        app.frame();
+        group.bench_function(BenchmarkId::from_parameter(size), |b| {
+            b.iter(|| black_box(app.frame()))
+        });
+    }
+    group.finish();
+
+    let mut group = c.benchmark_group("recompute");
+    group.sample_size(20);
+    for size in SIZES {
+        let mut app = HeadlessApp::new(session(size));
+        group.bench_function(BenchmarkId::from_parameter(size), |b| {
+            b.iter(|| {
+                app.state.session_changed();
+                black_box(app.frame())
+            })
+        });
+    }
+    group.finish();
+}
+
+criterion_group!(benches, shares);
+criterion_main!(benches);
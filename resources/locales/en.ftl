@@ -0,0 +1,313 @@
+## Shared
+
+add = Add
+remove = Remove
+copy = Copy
+clear = Clear
+load = Load
+import = Import
+export = Export
+reset = Reset
+example = e.g. { $value }
+nobody = (nobody)
+removed = (removed)
+unknown = (unknown)
+any = (any)
+name = Name
+total = Total
+receipt = Receipt
+participant = Participant
+person = Person
+amount = Amount
+date = Date
+label = Label
+category = Category
+tag = Tag
+format = Format
+month = Month
+paid-by = Paid by
+date-hint = YYYY-MM-DD
+date-hint-optional = YYYY-MM-DD, optional
+invalid-date = '{ $input }' is not a valid date
+invalid-amount = '{ $amount }' is not a valid amount
+wrote-file = Wrote { $path }
+no-participants = No participants
+showing-filtered = Showing receipts that pass the filter
+no-valid-iban = { $name } has no valid IBAN
+
+## Categories offered for receipts
+
+category-general = General
+category-groceries = Groceries
+category-dining = Dining
+category-transport = Transport
+category-lodging = Lodging
+category-utilities = Utilities
+category-entertainment = Entertainment
+category-other = Other
+
+## Menus
+
+session-menu = Session
+session-name = Session { $number }
+new-session = New session
+rename-session = Rename session
+rename = Rename
+delete-session = Delete session
+recurring-expenses = Recurring expenses
+totals-across-sessions = Totals across sessions
+save-workspace = Save workspace
+not-a-workspace = The file is not a workspace
+file-version-invalid = The file version is not a number: { $version }
+file-newer = The workspace was saved by a newer version of the app (file version { $version }, this one reads up to { $supported }). Update the app to open it.
+workspace-backed-up = The workspace file could not be read, so it was kept as { $path }
+view-menu = View
+reset-layout = Reset layout
+command-palette = Command palette
+command-palette-hint = Run a command or find a participant, receipt or session
+no-matches = No matches
+preferences = Preferences
+
+## Participants
+
+participants = Participants
+search-participants = Search participants
+still-owes = still owes { $amount }
+is-owed = is owed { $amount }
+manage-receipts = Manage receipts
+no-receipts-to-share = No receipts to share
+partaking-in = Currently partaking in:
+partake = partake
+ignore = ignore
+bank-details = Bank details
+bic-optional = BIC (optional)
+add-participant = Add participant
+participant-name = Participant's name
+participant-name-hint = e.g. Alex Smith
+from-contacts = From contacts
+everyone-is-here = Everyone is already here
+
+## Receipts
+
+receipts = Receipts
+search-receipts = Search receipts
+filter = Filter
+filter-active = Filter (active)
+assignment-matrix = Assignment matrix
+details = Details
+item-exclusions = Item exclusions ({ $count })
+manage-exclusions = Manage exclusions
+item-name = Item's name
+item-name-hint = e.g. Toothbrush
+item-cost = Item's cost
+exclude = Exclude
+item-excluded = (This individual item is excluded)
+add-receipt = Add receipt
+receipt-label = Receipt's label
+receipt-label-hint = e.g. Shopping trip
+receipt-total = Receipt's total
+options = Options
+auto-share = auto-share ({ $enabled ->
+    [true] enabled
+   *[false] disabled
+})
+everything = Everything
+
+## Filter and receipt details
+
+from = From
+to = To
+clear-filter = Clear filter
+dated = Dated
+tag-hint = e.g. trip-2026
+add-tag = Add tag
+
+## Exporting and importing
+
+exporting-options = Exporting options
+filtered-total = Filtered total
+current-total = Current total
+export-csv = Export in .csv
+export-txt = Export in .txt
+export-accounting = Export for ledger/beancount
+settle-up = Settle up
+statements = Statements
+statistics = Statistics
+report-title = { $session }, { $date }
+importing-options = Importing options
+import-bank-statement = Import bank statement
+paste-receipt = Paste receipt
+import-e-receipt = Import e-receipt
+splitwise-import-export = Splitwise import/export
+
+## Statistics
+
+breakdown = Breakdown
+key = Key
+by-category = By category
+by-participant = By participant
+over-time = Over time
+no-dated-receipts = No dated receipts
+export-csv-file = Export CSV
+
+## Recurring expenses
+
+schedule = Schedule
+schedule-weekly = weekly
+schedule-monthly = monthly
+schedule-yearly = yearly
+no-recurring-expenses = No recurring expenses
+paid-by-name = Paid by { $name }
+shared-by = Shared by { $names }
+upcoming = Upcoming
+skip-date = Skip { $date }
+invalid-occurrence-amount = Not more than zero, the date is skipped
+new-recurring-expense = New recurring expense
+recurring-label-hint = e.g. Rent
+first-date = First date
+share-weights = Share weights, 0 leaves someone out
+invalid-first-date = First date is not a valid date
+add-recurring-expense = Add recurring expense
+recurring-generated = { $count ->
+    [one] Added a receipt
+   *[other] Added { $count } receipts
+} for elapsed periods
+
+## Preferences
+
+language = Language
+new-receipts = New receipts
+share-with-everyone = Share with everyone
+numbers = Numbers
+currency-code = Currency code
+currency-symbol = Currency symbol
+accounting-format = Accounting format
+theme = Theme
+theme-dark = Dark
+theme-light = Light
+theme-classic = Classic
+theme-high-contrast = High contrast
+theme-copy-hint = Save a copy of the theme to change its colours
+colours = Colours
+background = Background
+debt = Debt
+credit = Credit
+exclusion = Exclusion
+warning = Warning
+error = Error
+delete-theme = Delete theme
+theme-name-hint = Name of the copy
+save-theme = Save as
+fonts = Fonts
+font-size = Font size
+interface-scale = Interface scale
+extra-font = Extra font for glyphs such as CJK
+load-font = Load font
+remove-font = Remove font
+not-a-font = { $path } is not a TrueType or OpenType font
+settings-save-failed = Failed to save settings: { $error }
+
+## Settlement and payments
+
+settlement = Settlement
+settled-up = Everyone is settled up
+transfer = { $from } pays { $to } { $amount }
+paid = Paid
+scan-to-pay = Scan to pay { $amount } to { $name }
+qr-export-hint = e.g. pay-alex
+save-png = Save PNG
+save-svg = Save SVG
+epc-remittance = Shared expenses from { $name }
+record-payment = Record payment
+payments-need-two = Payments need two participants
+payment-from = From
+payment-to = To
+note = Note
+record = Record
+recorded-payments = Recorded payments ({ $count })
+iban-length = An IBAN is 15 to 34 characters long
+iban-shape = An IBAN starts with a country code and two check digits
+iban-country-length = { $country } IBANs are { $length } characters long
+iban-check-digits = The IBAN check digits do not match
+bic-shape = A BIC is 8 or 11 letters and digits, e.g. NDEAFIHH
+epc-name-length = The payee's name must be 1 to 70 characters
+epc-amount = The amount must be between 0.01 and 999999999.99
+epc-too-long = The payment details are too long for a GiroCode
+
+## Statements
+
+statement-for = Statement for { $name }
+share-of-receipts = Share of receipts
+paid-for-group = Paid for the group
+pay-to = Pay { $amount } to { $name }
+reference = Reference
+virtual-barcode = Virtual barcode
+pays-you = { $from } pays you { $amount }
+pays = { $from } pays { $amount }
+period = Period (YYYYMM)
+due-date = Due date
+rf-references = Use RF creditor references
+invalid-due-date = Due date is not a valid date
+share-and-paid = Share { $share }  Paid { $paid }
+no-virtual-barcode = No virtual barcode: { $error }
+copy-statement = Copy statement
+no-reference = No reference
+reference-base = A reference base is 3 to 19 digits
+national-reference-length = A national reference is at most 20 digits
+creditor-reference-shape = A creditor reference is 1 to 21 letters and digits
+barcode-finnish-iban = Virtual barcodes need a Finnish IBAN
+barcode-amount = Virtual barcodes cover amounts below 1 000 000 €
+barcode-numeric-rf = Only numeric RF references fit in a virtual barcode
+
+## Plain-text accounting
+
+plain-text-accounting = Plain-text accounting
+accounts = Accounts
+accounts-default = Leave empty to use the default account
+credit-account = Credited when paying
+debit-account = Debited with their share
+copy-to-clipboard = Copy to clipboard
+accounting-no-payer = '{ $label }' has no payer
+accounting-not-shared = '{ $label }' is not shared with anyone
+accounting-unbalanced = '{ $payee }' does not balance ({ $amount } { $currency })
+accounting-few-postings = '{ $payee }' has fewer than two postings
+invalid-account = Invalid account name '{ $account }'
+account-double-space = Account '{ $account }' contains a double space
+invalid-beancount-account = '{ $account }' is not a valid beancount account
+
+## Imports
+
+statement-file = Statement file (CAMT.053, OFX or QIF)
+no-transactions = No transactions loaded
+imported = imported
+import-selected = Import selected ({ $count })
+email-file = Email file (.eml)
+no-email = No email loaded
+email-from = From: { $from }
+email-subject = Subject: { $subject }
+email-date = Date: { $date }
+receipt-format = Format: { $name }
+receipt-text = Receipt text
+paste-from-clipboard = Paste from clipboard
+parse = Parse
+total-amount = Total { $total }
+total-mismatch = Total { $total } does not match the items ({ $items })
+total-missing = No total found, using the items ({ $items })
+splitwise-import = Import a group export (.csv)
+splitwise-export = Export in Splitwise's format
+splitwise-imported = Imported { $expenses } expenses between { $people } people
+unrecognised-statement = Unrecognised statement format
+entry-without-amount = A transaction has no amount
+entry-without-id = The transaction on { $date } has no reference
+imports-not-remembered = Failed to remember imported transactions: { $error }
+email-without-boundary = Multipart message without a boundary
+email-without-text = The message has no text body
+not-splitwise-export = Not a Splitwise export: expected { $columns }
+splitwise-no-payer = '{ $description }' has no payer, skipped
+splitwise-does-not-add-up = '{ $description }' does not add up to its cost, skipped
+splitwise-several-payers = '{ $description }' has several payers, the largest is used
+splitwise-not-fully-shared = '{ $label }' is not fully shared, exporting { $amount }
+splitwise-payments-skipped = { $count ->
+    [one] A settle-up payment was
+   *[other] { $count } settle-up payments were
+} not imported
@@ -0,0 +1,313 @@
+## Shared
+
+add = Lisää
+remove = Poista
+copy = Kopioi
+clear = Tyhjennä
+load = Lataa
+import = Tuo
+export = Vie
+reset = Palauta
+example = esim. { $value }
+nobody = (ei kukaan)
+removed = (poistettu)
+unknown = (tuntematon)
+any = (mikä tahansa)
+name = Nimi
+total = Yhteensä
+receipt = Kuitti
+participant = Osallistuja
+person = Henkilö
+amount = Summa
+date = Päivämäärä
+label = Nimi
+category = Luokka
+tag = Tunniste
+format = Muoto
+month = Kuukausi
+paid-by = Maksaja
+date-hint = PP.KK.VVVV
+date-hint-optional = PP.KK.VVVV, valinnainen
+invalid-date = '{ $input }' ei ole kelvollinen päivämäärä
+invalid-amount = '{ $amount }' ei ole kelvollinen summa
+wrote-file = Kirjoitettiin { $path }
+no-participants = Ei osallistujia
+showing-filtered = Näytetään suodattimen läpäisevät kuitit
+no-valid-iban = Henkilöllä { $name } ei ole kelvollista IBANia
+
+## Categories offered for receipts
+
+category-general = Yleinen
+category-groceries = Ruokaostokset
+category-dining = Ravintolat
+category-transport = Liikenne
+category-lodging = Majoitus
+category-utilities = Asumiskulut
+category-entertainment = Viihde
+category-other = Muut
+
+## Menus
+
+session-menu = Istunto
+session-name = Istunto { $number }
+new-session = Uusi istunto
+rename-session = Nimeä istunto uudelleen
+rename = Nimeä
+delete-session = Poista istunto
+recurring-expenses = Toistuvat kulut
+totals-across-sessions = Summat kaikista istunnoista
+save-workspace = Tallenna työtila
+not-a-workspace = Tiedosto ei ole työtila
+file-version-invalid = Tiedoston versio ei ole numero: { $version }
+file-newer = Työtila on tallennettu sovelluksen uudemmalla versiolla (tiedoston versio { $version }, tämä lukee enintään version { $supported }). Päivitä sovellus avataksesi sen.
+workspace-backed-up = Työtilatiedostoa ei voitu lukea, joten se säilytettiin nimellä { $path }
+view-menu = Näkymä
+reset-layout = Palauta asettelu
+command-palette = Komentopaletti
+command-palette-hint = Suorita komento tai etsi osallistuja, kuitti tai istunto
+no-matches = Ei osumia
+preferences = Asetukset
+
+## Participants
+
+participants = Osallistujat
+search-participants = Hae osallistujia
+still-owes = velkaa vielä { $amount }
+is-owed = saa vielä { $amount }
+manage-receipts = Hallitse kuitteja
+no-receipts-to-share = Ei jaettavia kuitteja
+partaking-in = Osallistuu tällä hetkellä:
+partake = mukana
+ignore = ei mukana
+bank-details = Pankkitiedot
+bic-optional = BIC (valinnainen)
+add-participant = Lisää osallistuja
+participant-name = Osallistujan nimi
+participant-name-hint = esim. Matti Heikkinen
+from-contacts = Yhteystiedoista
+everyone-is-here = Kaikki ovat jo mukana
+
+## Receipts
+
+receipts = Kuitit
+search-receipts = Hae kuitteja
+filter = Suodatin
+filter-active = Suodatin (käytössä)
+assignment-matrix = Jakotaulukko
+details = Tiedot
+item-exclusions = Pois jätetyt tuotteet ({ $count })
+manage-exclusions = Hallitse pois jätettyjä
+item-name = Tuotteen nimi
+item-name-hint = esim. Hammasharja
+item-cost = Tuotteen hinta
+exclude = Jätä pois
+item-excluded = (Tämä tuote on jätetty pois)
+add-receipt = Lisää kuitti
+receipt-label = Kuitin nimi
+receipt-label-hint = esim. Kauppareissu
+receipt-total = Kuitin summa
+options = Valinnat
+auto-share = automaattinen jako ({ $enabled ->
+    [true] käytössä
+   *[false] pois käytöstä
+})
+everything = Kaikki
+
+## Filter and receipt details
+
+from = Alkaen
+to = Asti
+clear-filter = Tyhjennä suodatin
+dated = Päivätty
+tag-hint = esim. matka-2026
+add-tag = Lisää tunniste
+
+## Exporting and importing
+
+exporting-options = Vienti
+filtered-total = Suodatettu summa
+current-total = Nykyinen summa
+export-csv = Vie .csv-tiedostoon
+export-txt = Vie .txt-tiedostoon
+export-accounting = Vie ledger- tai beancount-muotoon
+settle-up = Tasaa velat
+statements = Erittelyt
+statistics = Tilastot
+report-title = { $session }, { $date }
+importing-options = Tuonti
+import-bank-statement = Tuo tiliote
+paste-receipt = Liitä kuitti
+import-e-receipt = Tuo sähköinen kuitti
+splitwise-import-export = Splitwise-tuonti ja -vienti
+
+## Statistics
+
+breakdown = Jaottelu
+key = Avain
+by-category = Luokittain
+by-participant = Osallistujittain
+over-time = Ajan mukaan
+no-dated-receipts = Ei päivättyjä kuitteja
+export-csv-file = Vie CSV
+
+## Recurring expenses
+
+schedule = Toistuvuus
+schedule-weekly = viikoittain
+schedule-monthly = kuukausittain
+schedule-yearly = vuosittain
+no-recurring-expenses = Ei toistuvia kuluja
+paid-by-name = Maksaja: { $name }
+shared-by = Jakajat: { $names }
+upcoming = Tulossa
+skip-date = Ohita { $date }
+invalid-occurrence-amount = Ei enemmän kuin nolla, päivä ohitetaan
+new-recurring-expense = Uusi toistuva kulu
+recurring-label-hint = esim. Vuokra
+first-date = Ensimmäinen päivä
+share-weights = Osuuksien painot, 0 jättää henkilön pois
+invalid-first-date = Ensimmäinen päivä ei ole kelvollinen päivämäärä
+add-recurring-expense = Lisää toistuva kulu
+recurring-generated = { $count ->
+    [one] Lisättiin kuitti
+   *[other] Lisättiin { $count } kuittia
+} kuluneilta jaksoilta
+
+## Preferences
+
+language = Kieli
+new-receipts = Uudet kuitit
+share-with-everyone = Jaa kaikkien kesken
+numbers = Luvut
+currency-code = Valuuttakoodi
+currency-symbol = Valuuttasymboli
+accounting-format = Kirjanpitomuoto
+theme = Teema
+theme-dark = Tumma
+theme-light = Vaalea
+theme-classic = Klassinen
+theme-high-contrast = Suuri kontrasti
+theme-copy-hint = Tallenna teemasta kopio muuttaaksesi sen värejä
+colours = Värit
+background = Tausta
+debt = Velka
+credit = Saatava
+exclusion = Pois jätetty
+warning = Varoitus
+error = Virhe
+delete-theme = Poista teema
+theme-name-hint = Kopion nimi
+save-theme = Tallenna nimellä
+fonts = Fontit
+font-size = Fonttikoko
+interface-scale = Käyttöliittymän koko
+extra-font = Lisäfontti esimerkiksi CJK-merkeille
+load-font = Lataa fontti
+remove-font = Poista fontti
+not-a-font = { $path } ei ole TrueType- tai OpenType-fontti
+settings-save-failed = Asetusten tallennus epäonnistui: { $error }
+
+## Settlement and payments
+
+settlement = Velkojen tasaus
+settled-up = Kaikki on tasattu
+transfer = { $from } maksaa henkilölle { $to } { $amount }
+paid = Maksettu
+scan-to-pay = Skannaa ja maksa { $amount } henkilölle { $name }
+qr-export-hint = esim. maksu-matille
+save-png = Tallenna PNG
+save-svg = Tallenna SVG
+epc-remittance = Yhteiset kulut: { $name }
+record-payment = Kirjaa maksu
+payments-need-two = Maksuun tarvitaan kaksi osallistujaa
+payment-from = Maksaja
+payment-to = Saaja
+note = Viesti
+record = Kirjaa
+recorded-payments = Kirjatut maksut ({ $count })
+iban-length = IBAN on 15–34 merkkiä pitkä
+iban-shape = IBAN alkaa maatunnuksella ja kahdella tarkistusnumerolla
+iban-country-length = Maatunnuksella { $country } IBAN on { $length } merkkiä pitkä
+iban-check-digits = IBANin tarkistusnumerot eivät täsmää
+bic-shape = BIC on 8 tai 11 kirjainta ja numeroa, esim. NDEAFIHH
+epc-name-length = Saajan nimessä on oltava 1–70 merkkiä
+epc-amount = Summan on oltava 0,01–999 999 999,99
+epc-too-long = Maksun tiedot ovat liian pitkät GiroCodeen
+
+## Statements
+
+statement-for = Erittely: { $name }
+share-of-receipts = Osuus kuiteista
+paid-for-group = Maksettu ryhmän puolesta
+pay-to = Maksa { $amount } henkilölle { $name }
+reference = Viite
+virtual-barcode = Virtuaaliviivakoodi
+pays-you = { $from } maksaa sinulle { $amount }
+pays = { $from } maksaa { $amount }
+period = Jakso (VVVVKK)
+due-date = Eräpäivä
+rf-references = Käytä RF-viitteitä
+invalid-due-date = Eräpäivä ei ole kelvollinen päivämäärä
+share-and-paid = Osuus { $share }  Maksettu { $paid }
+no-virtual-barcode = Ei virtuaaliviivakoodia: { $error }
+copy-statement = Kopioi erittely
+no-reference = Ei viitettä
+reference-base = Viitteen perusosa on 3–19 numeroa
+national-reference-length = Kotimaisessa viitteessä on enintään 20 numeroa
+creditor-reference-shape = RF-viitteessä on 1–21 kirjainta ja numeroa
+barcode-finnish-iban = Virtuaaliviivakoodi vaatii suomalaisen IBANin
+barcode-amount = Virtuaaliviivakoodiin mahtuvat alle 1 000 000 €:n summat
+barcode-numeric-rf = Virtuaaliviivakoodiin mahtuvat vain numeeriset RF-viitteet
+
+## Plain-text accounting
+
+plain-text-accounting = Tekstimuotoinen kirjanpito
+accounts = Tilit
+accounts-default = Jätä tyhjäksi käyttääksesi oletustiliä
+credit-account = Hyvitetään maksettaessa
+debit-account = Veloitetaan osuudella
+copy-to-clipboard = Kopioi leikepöydälle
+accounting-no-payer = Kuitilla '{ $label }' ei ole maksajaa
+accounting-not-shared = Kuittia '{ $label }' ei ole jaettu kenellekään
+accounting-unbalanced = '{ $payee }' ei täsmää ({ $amount } { $currency })
+accounting-few-postings = Tapahtumassa '{ $payee }' on alle kaksi vientiä
+invalid-account = Virheellinen tilin nimi '{ $account }'
+account-double-space = Tilin '{ $account }' nimessä on kaksi välilyöntiä peräkkäin
+invalid-beancount-account = '{ $account }' ei kelpaa beancount-tiliksi
+
+## Imports
+
+statement-file = Tiliotetiedosto (CAMT.053, OFX tai QIF)
+no-transactions = Ei ladattuja tapahtumia
+imported = tuotu
+import-selected = Tuo valitut ({ $count })
+email-file = Sähköpostitiedosto (.eml)
+no-email = Ei ladattua sähköpostia
+email-from = Lähettäjä: { $from }
+email-subject = Aihe: { $subject }
+email-date = Päivämäärä: { $date }
+receipt-format = Muoto: { $name }
+receipt-text = Kuitin teksti
+paste-from-clipboard = Liitä leikepöydältä
+parse = Jäsennä
+total-amount = Yhteensä { $total }
+total-mismatch = Summa { $total } ei täsmää tuotteiden summaan ({ $items })
+total-missing = Summaa ei löytynyt, käytetään tuotteiden summaa ({ $items })
+splitwise-import = Tuo ryhmän vienti (.csv)
+splitwise-export = Vie Splitwisen muodossa
+splitwise-imported = Tuotiin { $expenses } kulua { $people } henkilön kesken
+unrecognised-statement = Tiliotteen muotoa ei tunnistettu
+entry-without-amount = Tapahtumalta puuttuu summa
+entry-without-id = Tapahtumalta { $date } puuttuu viite
+imports-not-remembered = Tuotuja tapahtumia ei voitu tallentaa: { $error }
+email-without-boundary = Moniosaisesta viestistä puuttuu osien raja
+email-without-text = Viestissä ei ole tekstiosaa
+not-splitwise-export = Ei Splitwisen vienti: odotettiin sarakkeita { $columns }
+splitwise-no-payer = Kululla '{ $description }' ei ole maksajaa, ohitettiin
+splitwise-does-not-add-up = Kulun '{ $description }' osuudet eivät vastaa hintaa, ohitettiin
+splitwise-several-payers = Kululla '{ $description }' on useita maksajia, suurinta käytetään
+splitwise-not-fully-shared = Kuittia '{ $label }' ei ole jaettu kokonaan, viedään { $amount }
+splitwise-payments-skipped = { $count ->
+    [one] Tasausmaksua ei tuotu
+   *[other] { $count } tasausmaksua jätettiin tuomatta
+}
@@ -0,0 +1,354 @@
+use crate::locale::Message;
+use crate::{Participant, State};
+use chrono::NaiveDate;
+use serde::{Deserialize, Serialize};
+
+#[derive(Clone, Copy, PartialEq, Eq, Debug, Serialize, Deserialize)]
+pub enum Format {
+    Ledger,
+    Hledger,
+    Beancount,
+}
+
+impl Format {
+    pub const ALL: [Format; 3] = [Format::Ledger, Format::Hledger, Format::Beancount];
+
+    pub fn name(&self) -> &'static str {
+        match self {
+            Format::Ledger => "ledger",
+            Format::Hledger => "hledger",
+            Format::Beancount => "beancount",
+        }
+    }
+}
+
+#[derive(Clone, Debug)]
+pub struct Posting {
+    pub account: String,
+    /// Positive amounts are debits
+    pub cents: i64,
+}
+
+#[derive(Clone, Debug)]
+pub struct Transaction {
+    pub date: NaiveDate,
+    pub payee: String,
+    pub postings: Vec<Posting>,
+}
+
+/// Turns a participant's name into an account component, e.g.
+/// `matti heikkinen` into `Matti-Heikkinen`.
+fn account_component(name: &str) -> String {
+    let component = name
+        .split(|c: char| !c.is_alphanumeric())
+        .filter(|w| !w.is_empty())
+        .map(|w| {
+            let mut chars = w.chars();
+            chars
+                .next()
+                .into_iter()
+                .flat_map(char::to_uppercase)
+                .chain(chars)
+                .collect::<String>()
+        })
+        .collect::<Vec<_>>()
+        .join("-");
+    if component.is_empty() {
+        "Unnamed".to_string()
+    } else {
+        component
+    }
+}
+
+fn default_credit_account(name: &str) -> String {
+    format!("Liabilities:Payable:{}", account_component(name))
+}
+
+fn default_debit_account(name: &str) -> String {
+    format!("Expenses:Shared:{}", account_component(name))
+}
+
+/// Account credited when `participant` pays for a receipt.
+pub fn credit_account(participant: &Participant) -> String {
+    if participant.credit_account.is_empty() {
+        default_credit_account(&participant.name)
+    } else {
+        participant.credit_account.clone()
+    }
+}
+
+/// Account debited with `participant`'s share of a receipt.
+pub fn debit_account(participant: &Participant) -> String {
+    if participant.debit_account.is_empty() {
+        default_debit_account(&participant.name)
+    } else {
+        participant.debit_account.clone()
+    }
+}
+
+/// Splits `total` cents between `n` people so that the parts add up exactly,
+/// handing the leftover cents to the first ones.
+pub fn split_cents(total: i64, n: usize) -> Vec<i64> {
+    if n == 0 {
+        return Vec::new();
+    }
+    let n = n as i64;
+    (0..n)
+        .map(|i| total / n + i64::from(i < total % n))
+        .collect()
+}
+
+/// Builds one transaction per receipt that passes the filter and has a
+/// payer and participants, dated `date` unless the receipt has a date of its
+/// own. Receipts that cannot be booked are reported instead.
+pub fn transactions(state: &State, date: NaiveDate) -> (Vec<Transaction>, Vec<Message>) {
+    let mut transactions = Vec::new();
+    let mut errors = Vec::new();
+
+    for receipt in state
+        .receipts
+        .iter()
+        .filter(|r| state.filter.matches(state, r))
+    {
+        let total = receipt.total(state);
+        let sharers = state.sharers(receipt);
+        let payer = state
+            .payer_of(receipt)
+            .and_then(|id| state.participants.iter().find(|p| p.id == id));
+
+        let Some(payer) = payer else {
+            errors.push(Message::new("accounting-no-payer").arg("label", receipt.label.as_str()));
+            continue;
+        };
+        if sharers.is_empty() {
+            errors.push(Message::new("accounting-not-shared").arg("label", receipt.label.as_str()));
+            continue;
+        }
+
+        let cents = (total * 100.).round() as i64;
+        if cents == 0 {
+            continue;
+        }
+
+        let mut postings: Vec<Posting> = sharers
+            .iter()
+            .zip(split_cents(cents, sharers.len()))
+            .map(|(p, cents)| Posting {
+                account: debit_account(p),
+                cents,
+            })
+            .collect();
+        postings.push(Posting {
+            account: credit_account(payer),
+            cents: -cents,
+        });
+
+        transactions.push(Transaction {
+            date: state
+                .parent_of(receipt)
+                .unwrap_or(receipt)
+                .date
+                .unwrap_or(date),
+            payee: receipt.label.clone(),
+            postings,
+        });
+    }
+
+    (transactions, errors)
+}
+
+/// Checks that every transaction balances and that account names are
+/// acceptable to `format`.
+pub fn validate(format: Format, transactions: &[Transaction], currency: &str) -> Vec<Message> {
+    let mut errors = Vec::new();
+
+    for t in transactions {
+        let sum: i64 = t.postings.iter().map(|p| p.cents).sum();
+        if sum != 0 {
+            errors.push(
+                Message::new("accounting-unbalanced")
+                    .arg("payee", t.payee.as_str())
+                    .arg("amount", format!("{:.2}", sum as f64 / 100.))
+                    .arg("currency", currency),
+            );
+        }
+        if t.postings.len() < 2 {
+            errors.push(Message::new("accounting-few-postings").arg("payee", t.payee.as_str()));
+        }
+
+        for p in t.postings.iter() {
+            let components: Vec<&str> = p.account.split(':').collect();
+            if components.iter().any(|c| c.is_empty()) {
+                errors.push(Message::new("invalid-account").arg("account", p.account.as_str()));
+            } else if p.account.contains("  ") || p.account.contains('\t') {
+                // Two spaces separate the account from the amount
+                errors
+                    .push(Message::new("account-double-space").arg("account", p.account.as_str()));
+            } else if format == Format::Beancount {
+                let root_ok = matches!(
+                    components[0],
+                    "Assets" | "Liabilities" | "Equity" | "Income" | "Expenses"
+                );
+                let components_ok = components.iter().all(|c| {
+                    c.chars()
+                        .next()
+                        .is_some_and(|c| c.is_uppercase() || c.is_ascii_digit())
+                        && c.chars().all(|c| c.is_alphanumeric() || c == '-')
+                });
+                if !root_ok || !components_ok {
+                    errors.push(
+                        Message::new("invalid-beancount-account")
+                            .arg("account", p.account.as_str()),
+                    );
+                }
+            }
+        }
+    }
+
+    errors.sort();
+    errors.dedup();
+    errors
+}
+
+fn amount(cents: i64, currency: &str) -> String {
+    let sign = if cents < 0 { "-" } else { "" };
+    format!(
+        "{sign}{}.{:02} {currency}",
+        cents.abs() / 100,
+        cents.abs() % 100
+    )
+}
+
+pub fn render(format: Format, transactions: &[Transaction], currency: &str) -> String {
+    let mut s = String::new();
+
+    if format == Format::Beancount {
+        let mut accounts: Vec<&str> = transactions
+            .iter()
+            .flat_map(|t| t.postings.iter().map(|p| p.account.as_str()))
+            .collect();
+        accounts.sort();
+        accounts.dedup();
+
+        if let Some(first) = transactions.iter().map(|t| t.date).min() {
+            for account in accounts {
+                s += &format!("{first} open {account} {currency}\n");
+            }
+            s += "\n";
+        }
+    }
+
+    for t in transactions {
+        let payee = t.payee.replace('"', "'");
+        s += &match format {
+            Format::Ledger => format!("{} {payee}\n", t.date.format("%Y/%m/%d")),
+            Format::Hledger => format!("{} {payee}\n", t.date.format("%Y-%m-%d")),
+            Format::Beancount => format!("{} * \"{payee}\"\n", t.date.format("%Y-%m-%d")),
+        };
+
+        let width = t
+            .postings
+            .iter()
+            .map(|p| p.account.chars().count())
+            .max()
+            .unwrap_or(0);
+        for p in t.postings.iter() {
+            s += &format!(
+                "    {:width$}  {:>14}\n",
+                p.account,
+                amount(p.cents, currency)
+            );
+        }
+        s += "\n";
+    }
+
+    s
+}
+
+#[derive(Default)]
+pub struct AccountingExport {
+    pub open: bool,
+    output: String,
+    errors: Vec<Message>,
+}
+
+pub fn window(ui: &imgui::Ui, state: &mut State) {
+    let mut open = state.accounting_export.open;
+
+    ui.window(format!(
+        "{}###accounting",
+        state.locale.tr("plain-text-accounting")
+    ))
+    .size([520.0, 420.0], imgui::Condition::FirstUseEver)
+    .position([140., 40.], imgui::Condition::FirstUseEver)
+    .collapsible(false)
+    .opened(&mut open)
+    .build(|| {
+        let locale = &state.locale;
+        ui.text(locale.tr("accounts"));
+        ui.text_disabled(locale.tr("accounts-default"));
+        for participant in state.participants.iter_mut() {
+            ui.tree_node_config(format!("{}##accounts_{}", participant.name, participant.id))
+                .build(|| {
+                    ui.text_disabled(locale.tr("credit-account"));
+                    ui.input_text(
+                        format!("##credit_{}", participant.id),
+                        &mut participant.credit_account,
+                    )
+                    .hint(default_credit_account(&participant.name))
+                    .build();
+
+                    ui.text_disabled(locale.tr("debit-account"));
+                    ui.input_text(
+                        format!("##debit_{}", participant.id),
+                        &mut participant.debit_account,
+                    )
+                    .hint(default_debit_account(&participant.name))
+                    .build();
+                });
+        }
+
+        ui.separator();
+        if state.filter.is_active() {
+            ui.text_disabled(locale.tr("showing-filtered"));
+        }
+        let names = Format::ALL.map(|f| f.name());
+        let mut format = Format::ALL
+            .iter()
+            .position(|f| *f == state.settings.accounting_format)
+            .unwrap_or(0);
+        if ui.combo_simple_string(
+            format!("{}##accounting_format", locale.tr("format")),
+            &mut format,
+            &names,
+        ) {
+            state.settings.accounting_format = Format::ALL[format];
+            state.settings_changed();
+        }
+
+        if ui.button(state.locale.tr("export")) {
+            let format = state.settings.accounting_format;
+            let currency = state.settings.currency.clone();
+            let (transactions, mut errors) = transactions(state, chrono::Local::now().date_naive());
+            errors.extend(validate(format, &transactions, &currency));
+
+            let export = &mut state.accounting_export;
+            export.output = render(format, &transactions, &currency);
+            export.errors = errors;
+        }
+
+        let export = &state.accounting_export;
+        if !export.output.is_empty() {
+            ui.same_line();
+            if ui.button(state.locale.tr("copy-to-clipboard")) {
+                ui.set_clipboard_text(&export.output);
+            }
+        }
+
+        for error in export.errors.iter() {
+            ui.text_colored(state.settings.palette().error, state.locale.message(error));
+        }
+    });
+
+    state.accounting_export.open = open;
+}
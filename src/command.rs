@@ -0,0 +1,367 @@
+use crate::support::Chord;
+use crate::{filter, fuzzy, State};
+use glium::glutin::event::VirtualKeyCode;
+use imgui::Key;
+use uuid::Uuid;
+
+const PALETTE: Chord = Chord::ctrl(VirtualKeyCode::P);
+const PALETTE_ID: &str = "##command_palette";
+/// Entries shown at once, the rest are reached by narrowing the query
+const PALETTE_ENTRIES: usize = 12;
+
+/// Everything that can be done from the keyboard, with or without a shortcut.
+#[derive(Clone, Copy, PartialEq, Eq, Debug)]
+pub enum Action {
+    NewReceipt,
+    NewParticipant,
+    FocusSearch,
+    ExportTxt,
+    ExportCsv,
+    ExportAccounting,
+    SettleUp,
+    Statements,
+    Statistics,
+    AssignmentMatrix,
+    ImportBankStatement,
+    PasteReceipt,
+    ImportEReceipt,
+    Splitwise,
+    RecurringExpenses,
+    TotalsAcrossSessions,
+    NewSession,
+    SaveWorkspace,
+    ResetLayout,
+    Preferences,
+}
+
+impl Action {
+    pub const ALL: [Action; 20] = [
+        Action::NewReceipt,
+        Action::NewParticipant,
+        Action::FocusSearch,
+        Action::ExportTxt,
+        Action::ExportCsv,
+        Action::ExportAccounting,
+        Action::SettleUp,
+        Action::Statements,
+        Action::Statistics,
+        Action::AssignmentMatrix,
+        Action::ImportBankStatement,
+        Action::PasteReceipt,
+        Action::ImportEReceipt,
+        Action::Splitwise,
+        Action::RecurringExpenses,
+        Action::TotalsAcrossSessions,
+        Action::NewSession,
+        Action::SaveWorkspace,
+        Action::ResetLayout,
+        Action::Preferences,
+    ];
+
+    /// Id of the action's name in the catalogues, shared with the button or
+    /// menu item doing the same.
+    pub fn name(&self) -> &'static str {
+        match self {
+            Action::NewReceipt => "add-receipt",
+            Action::NewParticipant => "add-participant",
+            Action::FocusSearch => "search-receipts",
+            Action::ExportTxt => "export-txt",
+            Action::ExportCsv => "export-csv",
+            Action::ExportAccounting => "export-accounting",
+            Action::SettleUp => "settle-up",
+            Action::Statements => "statements",
+            Action::Statistics => "statistics",
+            Action::AssignmentMatrix => "assignment-matrix",
+            Action::ImportBankStatement => "import-bank-statement",
+            Action::PasteReceipt => "paste-receipt",
+            Action::ImportEReceipt => "import-e-receipt",
+            Action::Splitwise => "splitwise-import-export",
+            Action::RecurringExpenses => "recurring-expenses",
+            Action::TotalsAcrossSessions => "totals-across-sessions",
+            Action::NewSession => "new-session",
+            Action::SaveWorkspace => "save-workspace",
+            Action::ResetLayout => "reset-layout",
+            Action::Preferences => "preferences",
+        }
+    }
+
+    pub fn shortcut(&self) -> Option<Chord> {
+        match self {
+            Action::NewReceipt => Some(Chord::ctrl(VirtualKeyCode::N)),
+            Action::NewParticipant => Some(Chord::ctrl_shift(VirtualKeyCode::N)),
+            Action::FocusSearch => Some(Chord::ctrl(VirtualKeyCode::F)),
+            Action::ExportTxt => Some(Chord::ctrl(VirtualKeyCode::E)),
+            Action::ExportCsv => Some(Chord::ctrl_shift(VirtualKeyCode::E)),
+            Action::SaveWorkspace => Some(Chord::ctrl(VirtualKeyCode::S)),
+            Action::Preferences => Some(Chord::ctrl(VirtualKeyCode::Comma)),
+            _ => None,
+        }
+    }
+
+    pub fn run(&self, state: &mut State) {
+        match self {
+            Action::NewReceipt => state.commands.focus = Some(Focus::ReceiptLabel),
+            Action::NewParticipant => state.commands.focus = Some(Focus::ParticipantName),
+            Action::FocusSearch => state.commands.focus = Some(Focus::Search),
+            Action::ExportTxt => {
+                state.export_txt();
+            }
+            Action::ExportCsv => {
+                state.export_csv();
+            }
+            Action::ExportAccounting => state.accounting_export.open = true,
+            Action::SettleUp => state.settlement.open = true,
+            Action::Statements => state.statements.open = true,
+            Action::Statistics => state.statistics.open = true,
+            Action::AssignmentMatrix => state.matrix.open = true,
+            Action::ImportBankStatement => state.statement_import.open = true,
+            Action::PasteReceipt => state.paste_receipt.open = true,
+            Action::ImportEReceipt => state.eml_import.open = true,
+            Action::Splitwise => state.splitwise.open = true,
+            Action::RecurringExpenses => state.recurring_ui.open = true,
+            Action::TotalsAcrossSessions => state.workspace_ui.totals_open = true,
+            Action::NewSession => state.new_session(),
+            Action::SaveWorkspace => {
+                state.workspace_ui.message = match state.save_workspace() {
+                    Ok(note) => note.map(|note| state.locale.message(&note)),
+                    Err(e) => Some(state.locale.error(&e)),
+                };
+            }
+            Action::ResetLayout => state.layout.reset(),
+            Action::Preferences => state.preferences.open = true,
+        }
+    }
+}
+
+/// Inputs the keyboard can be sent to.
+#[derive(Clone, Copy, PartialEq, Eq, Debug)]
+pub enum Focus {
+    ParticipantName,
+    ReceiptLabel,
+    Search,
+}
+
+/// A participant or receipt to scroll to and expand.
+#[derive(Clone, Copy, PartialEq, Eq, Debug)]
+pub enum Reveal {
+    Participant(Uuid),
+    Receipt(Uuid),
+}
+
+/// What the command palette lists.
+#[derive(Clone, Copy, PartialEq, Eq, Debug)]
+enum Entry {
+    Action(Action),
+    Participant(Uuid),
+    Receipt(Uuid),
+    Session(usize),
+}
+
+impl Entry {
+    fn run(&self, state: &mut State) {
+        match self {
+            Entry::Action(action) => action.run(state),
+            Entry::Participant(id) => state.commands.reveal = Some(Reveal::Participant(*id)),
+            Entry::Receipt(id) => {
+                let hidden = state
+                    .receipts
+                    .iter()
+                    .find(|r| r.id == *id)
+                    .is_some_and(|r| !state.filter.matches(state, r));
+                if hidden {
+                    state.filter = filter::Filter::default();
+                    state.session_changed();
+                }
+                state.commands.reveal = Some(Reveal::Receipt(*id));
+            }
+            Entry::Session(i) => {
+                if *i != state.workspace.active {
+                    state.switch_session(*i);
+                }
+            }
+        }
+    }
+}
+
+#[derive(Default)]
+pub struct Commands {
+    /// Whether the palette should open on the next frame
+    palette: bool,
+    /// Whether the palette was shown on the last frame
+    showing: bool,
+    query: String,
+    selected: usize,
+    pub focus: Option<Focus>,
+    pub reveal: Option<Reveal>,
+}
+
+impl Commands {
+    /// Whether the keyboard is about to be sent to `target`, so that its
+    /// window can be brought to the front.
+    pub fn focuses(&self, target: Focus) -> bool {
+        self.focus == Some(target)
+    }
+
+    /// Sends the keyboard to the next widget if `target` asked for it.
+    pub fn focus_here(&mut self, ui: &imgui::Ui, target: Focus) {
+        if self.focus == Some(target) {
+            self.focus = None;
+            ui.set_keyboard_focus_here();
+        }
+    }
+
+    pub fn reveals_participant(&self) -> bool {
+        matches!(self.reveal, Some(Reveal::Participant(_)))
+    }
+
+    pub fn reveals_receipt(&self) -> bool {
+        matches!(self.reveal, Some(Reveal::Receipt(_)))
+    }
+
+    pub fn open_palette(&mut self) {
+        self.palette = true;
+    }
+}
+
+pub fn palette_shortcut() -> String {
+    PALETTE.label()
+}
+
+/// Runs the actions bound to `chords`. They wait while the palette is open.
+pub fn shortcuts(state: &mut State, chords: &[Chord]) {
+    if state.commands.showing {
+        return;
+    }
+    for chord in chords.iter() {
+        if *chord == PALETTE {
+            state.commands.open_palette();
+        } else if let Some(action) = Action::ALL.iter().find(|a| a.shortcut() == Some(*chord)) {
+            action.run(state);
+        }
+    }
+}
+
+/// Every entry with what it is shown as and what is shown beside it.
+fn entries(state: &State) -> Vec<(Entry, String, String)> {
+    let locale = &state.locale;
+    let mut entries: Vec<(Entry, String, String)> = Action::ALL
+        .iter()
+        .map(|a| {
+            (
+                Entry::Action(*a),
+                locale.tr(a.name()),
+                a.shortcut().map(|c| c.label()).unwrap_or_default(),
+            )
+        })
+        .collect();
+    entries.extend(state.participants.iter().map(|p| {
+        (
+            Entry::Participant(p.id),
+            p.name.clone(),
+            locale.tr("participant"),
+        )
+    }));
+    entries.extend(state.receipts.iter().map(|r| {
+        (
+            Entry::Receipt(r.id),
+            format!("{} {}", r.label, state.settings.number(f64::from(r.total))),
+            locale.tr("receipt"),
+        )
+    }));
+    entries.extend(
+        state
+            .workspace
+            .sessions
+            .iter()
+            .enumerate()
+            .filter(|(i, _)| *i != state.workspace.active)
+            .map(|(i, s)| (Entry::Session(i), s.name.clone(), locale.tr("session-menu"))),
+    );
+    entries
+}
+
+/// Lists the entries matching what is typed, best first. Enter or a click
+/// runs the selected one.
+pub fn palette(ui: &imgui::Ui, state: &mut State) {
+    let commands = &mut state.commands;
+    if commands.palette {
+        commands.palette = false;
+        commands.query.clear();
+        commands.selected = 0;
+        ui.open_popup(PALETTE_ID);
+    }
+
+    let mut run = None;
+    let showing = ui
+        .modal_popup_config(PALETTE_ID)
+        .title_bar(false)
+        .always_auto_resize(true)
+        .build(|| {
+            if ui.is_window_appearing() {
+                ui.set_keyboard_focus_here();
+            }
+            ui.set_next_item_width(400.);
+            let enter = ui
+                .input_text("##command_query", &mut state.commands.query)
+                .hint(state.locale.tr("command-palette-hint"))
+                .enter_returns_true(true)
+                .build();
+            if ui.is_item_edited() {
+                state.commands.selected = 0;
+            }
+
+            let mut entries: Vec<(i32, Entry, String, String)> = entries(state)
+                .into_iter()
+                .filter_map(|(entry, label, detail)| {
+                    fuzzy::find(&state.commands.query, &label)
+                        .map(|m| (m.score, entry, label, detail))
+                })
+                .collect();
+            // Stable, so equal scores keep actions before participants and
+            // so on
+            entries.sort_by_key(|e| -e.0);
+            entries.truncate(PALETTE_ENTRIES);
+
+            let commands = &mut state.commands;
+
+            if ui.is_key_pressed(Key::DownArrow) {
+                commands.selected += 1;
+            }
+            if ui.is_key_pressed(Key::UpArrow) {
+                commands.selected = commands.selected.saturating_sub(1);
+            }
+            commands.selected = commands.selected.min(entries.len().saturating_sub(1));
+
+            if entries.is_empty() {
+                ui.text_disabled(state.locale.tr("no-matches"));
+            }
+            for (i, (_, entry, label, detail)) in entries.iter().enumerate() {
+                if ui
+                    .selectable_config(format!("{label}##entry_{i}"))
+                    .selected(i == commands.selected)
+                    .build()
+                {
+                    run = Some(*entry);
+                }
+                if !detail.is_empty() {
+                    ui.same_line_with_pos(
+                        ui.window_content_region_max()[0] - ui.calc_text_size(detail)[0],
+                    );
+                    ui.text_disabled(detail);
+                }
+            }
+            if enter {
+                run = entries.get(commands.selected).map(|e| e.1);
+            }
+
+            if run.is_some() || ui.is_key_pressed(Key::Escape) {
+                ui.close_current_popup();
+            }
+        })
+        .is_some();
+    state.commands.showing = showing;
+
+    if let Some(entry) = run {
+        entry.run(state);
+    }
+}
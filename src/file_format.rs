@@ -0,0 +1,184 @@
+use crate::locale::Message;
+use crate::workspace::Workspace;
+use anyhow::{bail, Result};
+use serde::Serialize;
+use serde_json::{json, Map, Value};
+
+/// The version of the workspace file this build writes. Raise it, add the
+/// migration from the version before and a fixture whenever what is saved
+/// changes shape or meaning.
+pub const VERSION: u64 = 1;
+
+/// `MIGRATIONS[n]` upgrades a file of version `n` to version `n + 1`.
+const MIGRATIONS: [fn(Value) -> Result<Value>; VERSION as usize] = [from_unversioned];
+
+/// What is written to disk: the workspace with the version it was written
+/// in.
+#[derive(Serialize)]
+struct Envelope<'a> {
+    version: u64,
+    workspace: &'a Workspace,
+}
+
+/// The version `document` was written in. Files from before there were
+/// versions are the bare workspace.
+fn version(document: &Value) -> Result<u64> {
+    match document.get("version") {
+        None => Ok(0),
+        Some(value) => match value.as_u64() {
+            Some(version) => Ok(version),
+            None => bail!(Message::new("file-version-invalid").arg("version", value.to_string())),
+        },
+    }
+}
+
+fn newer_error(version: u64) -> anyhow::Error {
+    Message::new("file-newer")
+        .arg("version", version)
+        .arg("supported", VERSION)
+        .into()
+}
+
+pub fn to_json(workspace: &Workspace) -> Result<String> {
+    Ok(serde_json::to_string_pretty(&Envelope {
+        version: VERSION,
+        workspace,
+    })?)
+}
+
+/// Reads a workspace file of any version up to `VERSION`, migrating it
+/// step by step.
+pub fn from_json(text: &str) -> Result<Workspace> {
+    let mut document: Value = serde_json::from_str(text)?;
+    let version = version(&document)?;
+    if version > VERSION {
+        return Err(newer_error(version));
+    }
+    for migrate in MIGRATIONS[version as usize..].iter() {
+        document = migrate(document)?;
+    }
+
+    let Value::Object(mut envelope) = document else {
+        bail!(Message::new("not-a-workspace"));
+    };
+    let workspace = envelope
+        .remove("workspace")
+        .ok_or_else(|| Message::new("not-a-workspace"))?;
+    Ok(serde_json::from_value(workspace)?)
+}
+
+/// Fails if `existing`, the file about to be overwritten, was written by a
+/// newer version, whose data would be lost. Files that cannot be read are
+/// left to the caller, which backs them up.
+pub fn check_writable(existing: &str) -> Result<()> {
+    let newer = serde_json::from_str::<Value>(existing)
+        .ok()
+        .and_then(|document| version(&document).ok())
+        .filter(|version| *version > VERSION);
+    match newer {
+        Some(version) => Err(newer_error(version)),
+        None => Ok(()),
+    }
+}
+
+/// Sets `key` to `value` unless it is already there.
+fn fill(object: &mut Map<String, Value>, key: &str, value: Value) {
+    object.entry(key).or_insert(value);
+}
+
+/// The objects in the array `value`, if it is one.
+fn objects(value: Option<&mut Value>) -> impl Iterator<Item = &mut Map<String, Value>> {
+    value
+        .and_then(Value::as_array_mut)
+        .into_iter()
+        .flatten()
+        .filter_map(Value::as_object_mut)
+}
+
+/// Version 0 is the bare workspace, where fields added since sessions were
+/// first saved may be missing. They are written out with the values they
+/// were read as, and the workspace goes into the envelope.
+fn from_unversioned(workspace: Value) -> Result<Value> {
+    let Value::Object(mut workspace) = workspace else {
+        bail!(Message::new("not-a-workspace"));
+    };
+
+    fn participant(participant: &mut Map<String, Value>) {
+        for key in ["credit_account", "debit_account", "iban", "bic"] {
+            fill(participant, key, json!(""));
+        }
+    }
+    fn receipt(receipt: &mut Map<String, Value>) {
+        fill(receipt, "items", json!([]));
+        fill(receipt, "payer", Value::Null);
+        fill(receipt, "date", Value::Null);
+        fill(receipt, "category", json!(crate::default_category()));
+        fill(receipt, "tags", json!([]));
+    }
+
+    objects(workspace.get_mut("contacts")).for_each(participant);
+    for session in objects(workspace.get_mut("sessions")) {
+        objects(session.get_mut("participants")).for_each(participant);
+        objects(session.get_mut("receipts")).for_each(receipt);
+        if let Some(exclusions) = session.get_mut("exclusions").and_then(Value::as_object_mut) {
+            for items in exclusions.values_mut() {
+                objects(Some(items)).for_each(receipt);
+            }
+        }
+        fill(session, "payments", json!([]));
+        fill(session, "recurring", json!([]));
+    }
+
+    Ok(json!({ "version": 1, "workspace": workspace }))
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use std::fs;
+    use std::path::{Path, PathBuf};
+
+    fn fixture(version: u64) -> PathBuf {
+        Path::new(env!("CARGO_MANIFEST_DIR"))
+            .join("src/fixtures")
+            .join(format!("workspace-v{version}.json"))
+    }
+
+    fn read(version: u64) -> String {
+        fs::read_to_string(fixture(version)).unwrap()
+    }
+
+    /// Every fixture holds the same workspace, so each one read and written
+    /// again gives the current fixture.
+    #[test]
+    fn every_version_reads_as_the_current_one() {
+        let current: Value = serde_json::from_str(&read(VERSION)).unwrap();
+        for version in 0..=VERSION {
+            let workspace = from_json(&read(version)).unwrap();
+            let written: Value = serde_json::from_str(&to_json(&workspace).unwrap()).unwrap();
+            assert_eq!(written, current, "workspace-v{version}.json");
+        }
+    }
+
+    #[test]
+    fn writing_and_reading_keeps_everything() {
+        let workspace = from_json(&read(VERSION)).unwrap();
+        let once = to_json(&workspace).unwrap();
+        let twice = to_json(&from_json(&once).unwrap()).unwrap();
+        assert_eq!(
+            serde_json::from_str::<Value>(&once).unwrap(),
+            serde_json::from_str::<Value>(&twice).unwrap()
+        );
+    }
+
+    #[test]
+    fn newer_files_are_refused() {
+        let newer = json!({ "version": VERSION + 1, "workspace": {} }).to_string();
+        let error = from_json(&newer).unwrap_err().to_string();
+        assert!(error.contains("newer version"), "{error}");
+        assert!(check_writable(&newer).is_err());
+
+        assert!(check_writable(&read(VERSION)).is_ok());
+        assert!(check_writable("not json").is_ok());
+    }
+}
@@ -0,0 +1,204 @@
+use crate::locale::parse_date;
+use crate::{Receipt, State};
+use chrono::{Datelike, NaiveDate};
+
+/// Narrows the receipts shown and the totals exported, e.g. to groceries
+/// bought in March.
+#[derive(Default)]
+pub struct Filter {
+    from: String,
+    to: String,
+    /// `from` and `to` read when they were last edited, rather than for
+    /// every receipt on every frame
+    from_date: Option<NaiveDate>,
+    to_date: Option<NaiveDate>,
+    category: Option<String>,
+    tag: Option<String>,
+}
+
+impl Filter {
+    pub fn is_active(&self) -> bool {
+        self.from_date.is_some()
+            || self.to_date.is_some()
+            || self.category.is_some()
+            || self.tag.is_some()
+    }
+
+    /// Whether `receipt` passes. Excluded items are judged by the receipt
+    /// they were excluded from, and receipts without a date fail any date
+    /// range.
+    pub fn matches(&self, state: &State, receipt: &Receipt) -> bool {
+        let receipt = if receipt.exclusion {
+            state.parent_of(receipt).unwrap_or(receipt)
+        } else {
+            receipt
+        };
+        self.passes(receipt)
+    }
+
+    /// Whether `receipt` passes on its own dates, category and tags.
+    pub fn passes(&self, receipt: &Receipt) -> bool {
+        let (from, to) = (self.from_date, self.to_date);
+        if from.is_some() || to.is_some() {
+            let Some(date) = receipt.date else {
+                return false;
+            };
+            if from.is_some_and(|from| date < from) || to.is_some_and(|to| date > to) {
+                return false;
+            }
+        }
+
+        self.category
+            .as_ref()
+            .is_none_or(|c| receipt.category == *c)
+            && self.tag.as_ref().is_none_or(|t| receipt.tags.contains(t))
+    }
+}
+
+/// A combo of `options`, shown as `names`, with `any` first standing for
+/// `None`. Returns whether the value changed.
+fn any_combo(
+    ui: &imgui::Ui,
+    label: String,
+    any: String,
+    value: &mut Option<String>,
+    options: Vec<String>,
+    names: Vec<String>,
+) -> bool {
+    let mut index = value
+        .as_ref()
+        .and_then(|v| options.iter().position(|o| o == v))
+        .map_or(0, |i| i + 1);
+    let mut items = vec![any];
+    items.extend(names);
+    let changed = ui.combo_simple_string(label, &mut index, &items);
+    if changed {
+        *value = index.checked_sub(1).map(|i| options[i].clone());
+    }
+    changed
+}
+
+pub fn edit(ui: &imgui::Ui, state: &mut State) {
+    let categories = state.categories();
+    let tags = state.tags();
+    let locale = &state.locale;
+    let filter = &mut state.filter;
+
+    let mut changed = ui
+        .input_text(
+            format!("{}##filter_from", locale.tr("from")),
+            &mut filter.from,
+        )
+        .hint(locale.tr("date-hint"))
+        .build();
+    changed |= ui
+        .input_text(format!("{}##filter_to", locale.tr("to")), &mut filter.to)
+        .hint(locale.tr("date-hint"))
+        .build();
+    for date in [&filter.from, &filter.to] {
+        if !date.trim().is_empty() && parse_date(date).is_none() {
+            ui.text_colored(
+                state.settings.palette().error,
+                locale.tr_args("invalid-date", &[("input", date.as_str().into())]),
+            );
+        }
+    }
+    changed |= any_combo(
+        ui,
+        format!("{}##filter_category", locale.tr("category")),
+        locale.tr("any"),
+        &mut filter.category,
+        categories.clone(),
+        locale.categories(&categories),
+    );
+    changed |= any_combo(
+        ui,
+        format!("{}##filter_tag", locale.tr("tag")),
+        locale.tr("any"),
+        &mut filter.tag,
+        tags.clone(),
+        tags,
+    );
+
+    if ui.button(locale.tr("clear-filter")) {
+        *filter = Filter::default();
+        changed = true;
+    }
+
+    if changed {
+        filter.from_date = parse_date(&filter.from);
+        filter.to_date = parse_date(&filter.to);
+        state.session_changed();
+    }
+}
+
+/// Edits the date, category and tags of receipt `index`.
+pub fn details(ui: &imgui::Ui, state: &mut State, index: usize) {
+    let categories = state.categories();
+    let locale = &state.locale;
+    let receipt = &mut state.receipts[index];
+    let _id = ui.push_id(receipt.id.to_string());
+
+    let mut changed = false;
+    let mut dated = receipt.date.is_some();
+    if ui.checkbox(format!("{}##dated", locale.tr("dated")), &mut dated) {
+        receipt.date = dated.then(|| chrono::Local::now().date_naive());
+        changed = true;
+    }
+    if let Some(date) = receipt.date {
+        let mut ymd = [date.year(), date.month() as i32, date.day() as i32];
+        ui.same_line();
+        if ui.input_int3("##date", &mut ymd).build() {
+            if let Some(date) = NaiveDate::from_ymd_opt(ymd[0], ymd[1] as u32, ymd[2] as u32) {
+                receipt.date = Some(date);
+                changed = true;
+            }
+        }
+    }
+
+    let mut category = categories
+        .iter()
+        .position(|c| *c == receipt.category)
+        .unwrap_or(0);
+    if ui.combo_simple_string(
+        format!("{}##category", locale.tr("category")),
+        &mut category,
+        &locale.categories(&categories),
+    ) {
+        receipt.category = categories[category].clone();
+        changed = true;
+    }
+
+    let mut remove = None;
+    for (i, tag) in receipt.tags.iter().enumerate() {
+        if i > 0 {
+            ui.same_line();
+        }
+        if ui.small_button(format!("{tag} x")) {
+            remove = Some(i);
+        }
+    }
+    if let Some(i) = remove {
+        receipt.tags.remove(i);
+        changed = true;
+    }
+
+    ui.input_text("##tag", &mut state.tmp_tag)
+        .hint(locale.tr("tag-hint"))
+        .build();
+    ui.same_line();
+    let tag = state.tmp_tag.trim().to_string();
+    {
+        let _danger_token = ui.begin_disabled(tag.is_empty() || receipt.tags.contains(&tag));
+        if ui.button(locale.tr("add-tag")) {
+            receipt.tags.push(tag);
+            state.tmp_tag.clear();
+            changed = true;
+        }
+    }
+
+    // Dates, categories and tags decide what the filter lets through
+    if changed {
+        state.session_changed();
+    }
+}
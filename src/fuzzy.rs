@@ -0,0 +1,79 @@
+/// How `query` was found in a text, for ranking results and highlighting
+/// the characters that matched.
+#[derive(Clone, PartialEq, Eq, Debug)]
+pub struct Match {
+    pub score: i32,
+    /// Indices of the matched characters, not bytes, in ascending order
+    pub positions: Vec<usize>,
+}
+
+const CONSECUTIVE: i32 = 6;
+const WORD_START: i32 = 8;
+const FIRST_CHARACTER: i32 = 4;
+
+fn word_start(text: &[char], i: usize) -> bool {
+    i == 0
+        || !text[i - 1].is_alphanumeric()
+        || (text[i - 1].is_lowercase() && text[i].is_uppercase())
+}
+
+fn fold(c: char) -> char {
+    c.to_lowercase().next().unwrap_or(c)
+}
+
+/// Finds the characters of `query` in `text` in order, ignoring case and
+/// whitespace in the query. Runs of consecutive characters and matches at
+/// the start of words score higher, skipped characters lower. An empty
+/// query matches everything with a score of zero.
+pub fn find(query: &str, text: &str) -> Option<Match> {
+    let query: Vec<char> = query
+        .chars()
+        .filter(|c| !c.is_whitespace())
+        .map(fold)
+        .collect();
+    if query.is_empty() {
+        return Some(Match {
+            score: 0,
+            positions: Vec::new(),
+        });
+    }
+    let text: Vec<char> = text.chars().collect();
+    let folded: Vec<char> = text.iter().copied().map(fold).collect();
+
+    // Tries every place the first character occurs, as the first one is not
+    // always where the best run starts
+    (0..folded.len())
+        .filter(|start| folded[*start] == query[0])
+        .filter_map(|start| {
+            let mut positions = vec![start];
+            let mut next = start + 1;
+            for c in query[1..].iter() {
+                let found = (next..folded.len()).find(|i| folded[*i] == *c)?;
+                positions.push(found);
+                next = found + 1;
+            }
+            Some(Match {
+                score: score(&text, &positions),
+                positions,
+            })
+        })
+        .max_by_key(|m| m.score)
+}
+
+fn score(text: &[char], positions: &[usize]) -> i32 {
+    let mut score = 0;
+    for (n, i) in positions.iter().enumerate() {
+        score += 1;
+        if *i == 0 {
+            score += FIRST_CHARACTER;
+        }
+        if word_start(text, *i) {
+            score += WORD_START;
+        }
+        if n > 0 {
+            let gap = (*i - positions[n - 1] - 1) as i32;
+            score += if gap == 0 { CONSECUTIVE } else { -gap.min(5) };
+        }
+    }
+    score
+}
@@ -0,0 +1,64 @@
+use super::Transaction;
+use crate::locale::Message;
+use anyhow::Result;
+use roxmltree::{Document, Node};
+
+fn child<'a, 'i>(node: Node<'a, 'i>, path: &[&str]) -> Option<Node<'a, 'i>> {
+    path.iter().try_fold(node, |node, name| {
+        node.children().find(|n| n.tag_name().name() == *name)
+    })
+}
+
+fn text<'a>(node: Node<'a, '_>, path: &[&str]) -> Option<&'a str> {
+    child(node, path).and_then(|n| n.text()).map(str::trim)
+}
+
+/// Parses the booked entries (`<Ntry>`) of an ISO 20022 CAMT.053 statement.
+pub fn parse(input: &str) -> Result<Vec<Transaction>> {
+    let document = Document::parse(input)?;
+    let mut transactions = Vec::new();
+
+    for entry in document
+        .descendants()
+        .filter(|n| n.tag_name().name() == "Ntry")
+    {
+        let amount = text(entry, &["Amt"]).ok_or_else(|| Message::new("entry-without-amount"))?;
+        let mut amount: f32 = amount
+            .parse()
+            .map_err(|_| Message::new("invalid-amount").arg("amount", amount))?;
+        if text(entry, &["CdtDbtInd"]) == Some("DBIT") {
+            amount = -amount;
+        }
+
+        let date = text(entry, &["BookgDt", "Dt"])
+            .or_else(|| text(entry, &["BookgDt", "DtTm"]))
+            .or_else(|| text(entry, &["ValDt", "Dt"]))
+            .unwrap_or_default();
+
+        let details = child(entry, &["NtryDtls", "TxDtls"]);
+        let id = text(entry, &["AcctSvcrRef"])
+            .or_else(|| details.and_then(|d| text(d, &["Refs", "AcctSvcrRef"])))
+            .or_else(|| details.and_then(|d| text(d, &["Refs", "EndToEndId"])))
+            .or_else(|| text(entry, &["NtryRef"]))
+            .ok_or_else(|| Message::new("entry-without-id").arg("date", date))?;
+
+        let counterparty = if amount < 0. { "Cdtr" } else { "Dbtr" };
+        let description = details
+            .and_then(|d| {
+                text(d, &["RltdPties", counterparty, "Nm"])
+                    .or_else(|| text(d, &["RltdPties", counterparty, "Pty", "Nm"]))
+                    .or_else(|| text(d, &["RmtInf", "Ustrd"]))
+            })
+            .or_else(|| text(entry, &["AddtlNtryInf"]))
+            .unwrap_or_default();
+
+        transactions.push(Transaction {
+            id: format!("camt:{id}"),
+            date: date.chars().take(10).collect(),
+            amount,
+            description: description.to_string(),
+        });
+    }
+
+    Ok(transactions)
+}
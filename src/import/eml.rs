@@ -0,0 +1,497 @@
+use crate::locale::Message;
+use crate::receipt_text::{self, ParsedReceipt};
+use crate::{Receipt, State};
+use anyhow::Result;
+use base64::Engine;
+use std::fs;
+
+/// The parts of an e-receipt email needed to build a `Receipt`.
+#[derive(Clone, Debug)]
+pub struct EReceipt {
+    pub from: String,
+    pub subject: String,
+    pub date: String,
+    pub body: String,
+}
+
+impl EReceipt {
+    /// Display name of the sender, e.g. `Prisma` for `"Prisma" <kuitti@s-kanava.fi>`.
+    pub fn sender_name(&self) -> &str {
+        let name = self.from.split('<').next().unwrap_or_default().trim();
+        let name = name.trim_matches('"');
+        if name.is_empty() {
+            self.from.trim_matches(['<', '>'])
+        } else {
+            name
+        }
+    }
+}
+
+type Headers = Vec<(String, String)>;
+
+/// Where `needle` first starts in `haystack`.
+fn find(haystack: &[u8], needle: &[u8]) -> Option<usize> {
+    haystack.windows(needle.len()).position(|w| w == needle)
+}
+
+/// Splits `bytes` around the first `separator`.
+fn split_once<'a>(bytes: &'a [u8], separator: &[u8]) -> Option<(&'a [u8], &'a [u8])> {
+    let at = find(bytes, separator)?;
+    Some((&bytes[..at], &bytes[at + separator.len()..]))
+}
+
+/// Splits an entity into its headers and its body, which is left as bytes
+/// until its transfer encoding and charset are known.
+fn split_message(raw: &[u8]) -> (Headers, &[u8]) {
+    let (head, body) = split_once(raw, b"\r\n\r\n")
+        .or_else(|| split_once(raw, b"\n\n"))
+        .unwrap_or((raw, b""));
+
+    // Headers are ASCII with encoded words, but some senders write them in
+    // UTF-8 or Latin-1 as they are
+    let head = match std::str::from_utf8(head) {
+        Ok(head) => head.to_string(),
+        Err(_) => decode_charset(head, "iso-8859-1"),
+    };
+    let mut headers: Headers = Vec::new();
+    for line in head.lines() {
+        if line.starts_with([' ', '\t']) {
+            // Folded continuation of the previous header
+            if let Some((_, value)) = headers.last_mut() {
+                value.push(' ');
+                value.push_str(line.trim());
+            }
+        } else if let Some((name, value)) = line.split_once(':') {
+            headers.push((name.trim().to_lowercase(), value.trim().to_string()));
+        }
+    }
+
+    (headers, body)
+}
+
+fn header<'a>(headers: &'a Headers, name: &str) -> &'a str {
+    headers
+        .iter()
+        .find(|(n, _)| n == name)
+        .map(|(_, v)| v.as_str())
+        .unwrap_or_default()
+}
+
+/// Looks up a parameter such as `charset` in a header value like
+/// `text/plain; charset="utf-8"`.
+fn parameter(value: &str, name: &str) -> Option<String> {
+    value.split(';').skip(1).find_map(|p| {
+        let (key, value) = p.split_once('=')?;
+        (key.trim().eq_ignore_ascii_case(name)).then(|| value.trim().trim_matches('"').to_string())
+    })
+}
+
+/// What windows-1252 puts in 0x80–0x9F, where Latin-1 has control
+/// characters. The five unused bytes are kept as those.
+const WINDOWS_1252: [char; 32] = [
+    '€', '\u{81}', '‚', 'ƒ', '„', '…', '†', '‡', 'ˆ', '‰', 'Š', '‹', 'Œ', '\u{8d}', 'Ž', '\u{8f}',
+    '\u{90}', '‘', '’', '“', '”', '•', '–', '—', '˜', '™', 'š', '›', 'œ', '\u{9d}', 'ž', 'Ÿ',
+];
+
+fn decode_charset(bytes: &[u8], charset: &str) -> String {
+    match charset.to_lowercase().as_str() {
+        "iso-8859-1" | "iso-8859-15" | "latin1" => bytes.iter().map(|&b| b as char).collect(),
+        "windows-1252" | "cp1252" => bytes
+            .iter()
+            .map(|&b| match b {
+                0x80..=0x9f => WINDOWS_1252[usize::from(b - 0x80)],
+                b => b as char,
+            })
+            .collect(),
+        _ => String::from_utf8_lossy(bytes).into_owned(),
+    }
+}
+
+fn decode_quoted_printable(bytes: &[u8]) -> Vec<u8> {
+    let mut out = Vec::with_capacity(bytes.len());
+    let mut i = 0;
+    while i < bytes.len() {
+        match bytes[i] {
+            b'=' if bytes[i + 1..].starts_with(b"\r\n") => i += 3,
+            b'=' if bytes[i + 1..].starts_with(b"\n") => i += 2,
+            b'=' => match bytes
+                .get(i + 1..i + 3)
+                .and_then(|h| std::str::from_utf8(h).ok())
+                .and_then(|h| u8::from_str_radix(h, 16).ok())
+            {
+                Some(b) => {
+                    out.push(b);
+                    i += 3;
+                }
+                None => {
+                    out.push(b'=');
+                    i += 1;
+                }
+            },
+            b => {
+                out.push(b);
+                i += 1;
+            }
+        }
+    }
+    out
+}
+
+fn decode_body(headers: &Headers, body: &[u8]) -> Result<String> {
+    let charset = parameter(header(headers, "content-type"), "charset").unwrap_or_default();
+    let bytes = match header(headers, "content-transfer-encoding")
+        .to_lowercase()
+        .as_str()
+    {
+        "base64" => base64::engine::general_purpose::STANDARD.decode(
+            body.iter()
+                .copied()
+                .filter(|b| !b.is_ascii_whitespace())
+                .collect::<Vec<u8>>(),
+        )?,
+        "quoted-printable" => decode_quoted_printable(body),
+        _ => body.to_vec(),
+    };
+    Ok(decode_charset(&bytes, &charset))
+}
+
+/// Decodes RFC 2047 encoded words such as `=?UTF-8?Q?K-Citymarket_kuitti?=`.
+fn decode_header(value: &str) -> String {
+    let mut out = String::new();
+    let mut rest = value;
+    let mut encoded = false;
+    while let Some(start) = rest.find("=?") {
+        let Some((charset, encoding, text, len)) =
+            rest[start + 2..].split_once('?').and_then(|(charset, r)| {
+                let (encoding, r) = r.split_once('?')?;
+                let end = r.find("?=")?;
+                let len = charset.len() + encoding.len() + end + 4;
+                Some((charset, encoding, &r[..end], len))
+            })
+        else {
+            break;
+        };
+
+        // Whitespace between adjacent encoded words is dropped
+        let between = &rest[..start];
+        if !(encoded && between.trim().is_empty()) {
+            out.push_str(between);
+        }
+
+        let bytes = match encoding.to_uppercase().as_str() {
+            "B" => base64::engine::general_purpose::STANDARD
+                .decode(text)
+                .unwrap_or_default(),
+            _ => decode_quoted_printable(text.replace('_', " ").as_bytes()),
+        };
+        out.push_str(&decode_charset(&bytes, charset));
+        encoded = true;
+        rest = &rest[start + 2 + len..];
+    }
+    out.push_str(rest);
+    out
+}
+
+/// Returns the preferred text of a (possibly multipart) entity, plain text
+/// over HTML, and whether it was HTML.
+fn text_part(headers: &Headers, body: &[u8]) -> Result<Option<(bool, String)>> {
+    let content_type = header(headers, "content-type");
+    let mime = content_type
+        .split(';')
+        .next()
+        .unwrap_or("text/plain")
+        .trim()
+        .to_lowercase();
+
+    if mime.starts_with("multipart/") {
+        let boundary = parameter(content_type, "boundary")
+            .ok_or_else(|| Message::new("email-without-boundary"))?;
+        let delimiter = format!("--{boundary}");
+
+        let mut html = None;
+        let mut rest = match split_once(body, delimiter.as_bytes()) {
+            Some((_, rest)) => rest,
+            None => b"",
+        };
+        while !rest.is_empty() && !rest.starts_with(b"--") {
+            let (part, next) = split_once(rest, delimiter.as_bytes()).unwrap_or((rest, b""));
+            rest = next;
+            let start = part
+                .iter()
+                .position(|b| !matches!(b, b'\r' | b'\n'))
+                .unwrap_or(part.len());
+            let (headers, body) = split_message(&part[start..]);
+            match text_part(&headers, body)? {
+                Some((false, text)) => return Ok(Some((false, text))),
+                Some(found) => html = html.or(Some(found)),
+                None => {}
+            }
+        }
+        return Ok(html);
+    }
+
+    match mime.as_str() {
+        "text/plain" | "" => Ok(Some((false, decode_body(headers, body)?))),
+        "text/html" => Ok(Some((true, decode_body(headers, body)?))),
+        _ => Ok(None),
+    }
+}
+
+/// Reduces an HTML body to text lines, keeping table rows on one line so
+/// that item labels stay next to their prices.
+fn html_to_text(html: &str) -> String {
+    let mut text = String::new();
+    let mut rest = html;
+    while let Some(start) = rest.find('<') {
+        text.push_str(&rest[..start]);
+        let Some(close) = rest[start..].find('>') else {
+            rest = &rest[start..];
+            break;
+        };
+        let end = start + close + 1;
+        let tag = rest[start + 1..end - 1]
+            .trim_start_matches('/')
+            .split(|c: char| c.is_whitespace() || c == '/')
+            .next()
+            .unwrap_or_default()
+            .to_lowercase();
+        match tag.as_str() {
+            "br" | "p" | "div" | "tr" | "li" | "h1" | "h2" | "h3" | "table" => text.push('\n'),
+            "td" | "th" => text.push_str("   "),
+            "style" | "script" if !rest[start + 1..].starts_with('/') => {
+                let closing = format!("</{tag}");
+                let skip = rest[end..].to_ascii_lowercase().find(&closing).unwrap_or(0);
+                rest = &rest[end + skip..];
+                continue;
+            }
+            _ => {}
+        }
+        rest = &rest[end..];
+    }
+    text.push_str(rest);
+
+    let entities = [
+        ("&nbsp;", " "),
+        ("&euro;", "€"),
+        ("&auml;", "ä"),
+        ("&Auml;", "Ä"),
+        ("&ouml;", "ö"),
+        ("&Ouml;", "Ö"),
+        ("&aring;", "å"),
+        ("&Aring;", "Å"),
+        ("&lt;", "<"),
+        ("&gt;", ">"),
+        ("&quot;", "\""),
+        ("&#39;", "'"),
+        ("&amp;", "&"),
+    ];
+    let text = entities
+        .iter()
+        .fold(text, |text, (entity, c)| text.replace(entity, c));
+
+    text.lines()
+        .map(str::trim)
+        .filter(|l| !l.is_empty())
+        .collect::<Vec<_>>()
+        .join("\n")
+}
+
+/// Turns an RFC 5322 date (`Tue, 17 Oct 2026 14:03:00 +0300`) into `2026-10-17`.
+fn format_date(date: &str) -> String {
+    const MONTHS: [&str; 12] = [
+        "jan", "feb", "mar", "apr", "may", "jun", "jul", "aug", "sep", "oct", "nov", "dec",
+    ];
+    let tokens: Vec<&str> = date
+        .split_whitespace()
+        .filter(|t| !t.ends_with(','))
+        .collect();
+    match tokens.as_slice() {
+        [day, month, year, ..] => {
+            let month = MONTHS
+                .iter()
+                .position(|m| month.to_lowercase().starts_with(m));
+            match (day.parse::<u32>(), month) {
+                (Ok(day), Some(month)) => format!("{year}-{:02}-{day:02}", month + 1),
+                _ => date.to_string(),
+            }
+        }
+        _ => date.to_string(),
+    }
+}
+
+/// Reads a saved `.eml` message and extracts its receipt text.
+pub fn read_eml(path: &str) -> Result<EReceipt> {
+    parse_eml(&fs::read(path)?)
+}
+
+/// Extracts the receipt text of a raw message, decoding each part from its
+/// own charset.
+fn parse_eml(raw: &[u8]) -> Result<EReceipt> {
+    let (headers, body) = split_message(raw);
+
+    let (html, body) =
+        text_part(&headers, body)?.ok_or_else(|| Message::new("email-without-text"))?;
+
+    Ok(EReceipt {
+        from: decode_header(header(&headers, "from")),
+        subject: decode_header(header(&headers, "subject")),
+        date: format_date(header(&headers, "date")),
+        body: if html { html_to_text(&body) } else { body },
+    })
+}
+
+#[derive(Default)]
+pub struct EmlImport {
+    pub open: bool,
+    path: String,
+    label: String,
+    preview: Option<(EReceipt, &'static str, ParsedReceipt)>,
+    error: Option<anyhow::Error>,
+}
+
+impl EmlImport {
+    fn load(&mut self) {
+        self.error = None;
+        self.preview = None;
+        match read_eml(&self.path) {
+            Ok(email) => {
+                let (name, parsed) = receipt_text::parse(&email.body);
+                let merchant = parsed
+                    .merchant
+                    .clone()
+                    .unwrap_or_else(|| email.sender_name().to_string());
+                self.label = format!("{merchant} {}", email.date).trim().to_string();
+                self.preview = Some((email, name, parsed));
+            }
+            Err(e) => self.error = Some(e),
+        }
+    }
+}
+
+pub fn window(ui: &imgui::Ui, state: &mut State) {
+    let mut open = state.eml_import.open;
+
+    ui.window(format!(
+        "{}###eml_import",
+        state.locale.tr("import-e-receipt")
+    ))
+    .size([500.0, 420.0], imgui::Condition::FirstUseEver)
+    .position([150., 40.], imgui::Condition::FirstUseEver)
+    .collapsible(false)
+    .opened(&mut open)
+    .build(|| {
+        let locale = &state.locale;
+        let import = &mut state.eml_import;
+
+        ui.text(locale.tr("email-file"));
+        ui.input_text("##eml_path", &mut import.path)
+            .hint(locale.tr_args("example", &[("value", "receipts/prisma.eml".into())]))
+            .build();
+        ui.same_line();
+        {
+            let _danger_token = ui.begin_disabled(import.path.is_empty());
+            if ui.button(locale.tr("load")) {
+                import.load();
+            }
+        }
+
+        if let Some(error) = &import.error {
+            ui.text_colored(state.settings.palette().error, locale.error(error));
+        }
+
+        let Some((email, name, parsed)) = &import.preview else {
+            ui.text_disabled(locale.tr("no-email"));
+            return;
+        };
+
+        let date = chrono::NaiveDate::parse_from_str(&email.date, "%Y-%m-%d").ok();
+        ui.text_disabled(locale.tr_args("email-from", &[("from", email.from.as_str().into())]));
+        ui.text_disabled(locale.tr_args(
+            "email-subject",
+            &[("subject", email.subject.as_str().into())],
+        ));
+        ui.text_disabled(format!(
+            "{}  {}",
+            locale.tr_args(
+                "email-date",
+                &[(
+                    "date",
+                    date.map_or(email.date.clone(), |d| locale.language.date(d))
+                        .into()
+                )],
+            ),
+            locale.tr_args("receipt-format", &[("name", (*name).into())])
+        ));
+
+        ui.text(locale.tr("receipt-label"));
+        ui.input_text("##eml_label", &mut import.label)
+            .hint(locale.tr_args("example", &[("value", "Prisma Kamppi".into())]))
+            .build();
+
+        let total = receipt_text::preview(ui, locale, &state.settings.palette(), parsed);
+
+        let _danger_token = ui.begin_disabled(import.label.is_empty() || total <= 0.);
+        if ui.button(locale.tr("add-receipt")) {
+            let mut receipt = Receipt::new(import.label.clone(), total, false);
+            receipt.items = parsed.items.clone();
+            receipt.date = date;
+            import.preview = None;
+            import.label.clear();
+            state.add_receipt(receipt);
+        }
+    });
+
+    state.eml_import.open = open;
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    /// A message with `body` as its only part, sent as it is.
+    fn message(charset: &str, body: &[u8]) -> Vec<u8> {
+        let mut raw = format!(
+            "From: \"Prisma\" <kuitti@s-kanava.fi>\r\n\
+             Subject: =?iso-8859-1?Q?S=E4hk=F6inen_kuitti?=\r\n\
+             Date: Tue, 17 Oct 2026 14:03:00 +0300\r\n\
+             Content-Type: text/plain; charset={charset}\r\n\
+             Content-Transfer-Encoding: 8bit\r\n\r\n"
+        )
+        .into_bytes();
+        raw.extend_from_slice(body);
+        raw
+    }
+
+    #[test]
+    fn eight_bit_latin1() {
+        let email = parse_eml(&message("iso-8859-1", b"P\xe4\xe4ryn\xe4t 2,49\r\n")).unwrap();
+        assert_eq!(email.body, "Päärynät 2,49\r\n");
+        assert_eq!(email.subject, "Sähköinen kuitti");
+        assert_eq!(email.sender_name(), "Prisma");
+        assert_eq!(email.date, "2026-10-17");
+    }
+
+    #[test]
+    fn windows_1252() {
+        let email = parse_eml(&message(
+            "windows-1252",
+            b"Yhteens\xe4 12,50 \x80 \x96 kiitos",
+        ))
+        .unwrap();
+        assert_eq!(email.body, "Yhteensä 12,50 € – kiitos");
+    }
+
+    #[test]
+    fn parts_keep_their_own_charsets() {
+        let raw = b"Content-Type: multipart/alternative; boundary=\"b\"\r\n\r\n\
+            --b\r\n\
+            Content-Type: text/html; charset=utf-8\r\n\r\n\
+            <p>Maito</p>\r\n\
+            --b\r\n\
+            Content-Type: text/plain; charset=iso-8859-1\r\n\r\n\
+            Kev\xe4t\r\n\
+            --b--\r\n";
+        let email = parse_eml(raw).unwrap();
+        assert_eq!(email.body, "Kev\u{e4}t\r\n");
+    }
+}
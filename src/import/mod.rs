@@ -0,0 +1,196 @@
+use crate::locale::Message;
+use crate::support::file_system;
+use crate::{Receipt, State};
+use anyhow::Result;
+use chrono::NaiveDate;
+use std::collections::HashSet;
+use std::fs;
+use std::path::Path;
+
+mod camt;
+pub mod eml;
+mod ofx;
+mod qif;
+
+const IMPORTED_FILE: &str = "imported_transactions.json";
+
+/// A single booked transaction read from a bank statement.
+/// Negative amounts are money leaving the account.
+#[derive(Clone, Debug)]
+pub struct Transaction {
+    pub id: String,
+    pub date: String,
+    pub amount: f32,
+    pub description: String,
+}
+
+/// Reads a transaction date in any of the layouts the statement formats use.
+fn parse_date(date: &str) -> Option<NaiveDate> {
+    [
+        "%Y-%m-%d", "%Y%m%d", "%d.%m.%Y", "%m/%d/%Y", "%m/%d'%y", "%m/%d/%y",
+    ]
+    .iter()
+    .find_map(|format| NaiveDate::parse_from_str(date.trim(), format).ok())
+}
+
+/// Reads a CAMT.053, OFX or QIF statement, picking the parser from the
+/// file extension and falling back to sniffing the contents.
+pub fn read_statement(path: &str) -> Result<Vec<Transaction>> {
+    let input = fs::read_to_string(path)?;
+    let extension = Path::new(path)
+        .extension()
+        .and_then(|e| e.to_str())
+        .unwrap_or_default()
+        .to_lowercase();
+
+    match extension.as_str() {
+        "xml" | "camt" | "053" => camt::parse(&input),
+        "ofx" | "qfx" => ofx::parse(&input),
+        "qif" => qif::parse(&input),
+        _ if input.contains("<BkToCstmrStmt") => camt::parse(&input),
+        _ if input.contains("<OFX>") || input.contains("OFXHEADER") => ofx::parse(&input),
+        _ if input.trim_start().starts_with("!Type:") => qif::parse(&input),
+        _ => Err(Message::new("unrecognised-statement").into()),
+    }
+}
+
+#[derive(Default)]
+pub struct StatementImport {
+    pub open: bool,
+    path: String,
+    transactions: Vec<(Transaction, bool)>,
+    imported: Option<HashSet<String>>,
+    error: Option<anyhow::Error>,
+}
+
+impl StatementImport {
+    fn imported(&mut self) -> &mut HashSet<String> {
+        self.imported.get_or_insert_with(|| {
+            file_system::read_json(IMPORTED_FILE)
+                .ok()
+                .flatten()
+                .unwrap_or_default()
+        })
+    }
+
+    fn load(&mut self) {
+        self.error = None;
+        match read_statement(&self.path) {
+            Ok(transactions) => {
+                let imported = self.imported().clone();
+                self.transactions = transactions
+                    .into_iter()
+                    .map(|t| {
+                        let selected = t.amount < 0. && !imported.contains(&t.id);
+                        (t, selected)
+                    })
+                    .collect();
+            }
+            Err(e) => {
+                self.transactions.clear();
+                self.error = Some(e);
+            }
+        }
+    }
+
+    /// Turns the selected transactions into receipts and remembers their ids.
+    fn commit(&mut self) -> Vec<Receipt> {
+        let selected: Vec<Transaction> = self
+            .transactions
+            .iter()
+            .filter(|(_, selected)| *selected)
+            .map(|(t, _)| t.clone())
+            .collect();
+
+        let imported = self.imported();
+        imported.extend(selected.iter().map(|t| t.id.clone()));
+        if let Err(e) = file_system::write_json(IMPORTED_FILE, imported) {
+            let message = Message::new("imports-not-remembered").arg("error", e.to_string());
+            self.error = Some(message.into());
+        }
+        self.transactions.clear();
+
+        selected
+            .into_iter()
+            .map(|t| {
+                let date = parse_date(&t.date);
+                let label = if t.description.is_empty() {
+                    t.date
+                } else {
+                    t.description
+                };
+                let mut receipt = Receipt::new(label, t.amount.abs(), false);
+                receipt.date = date;
+                receipt
+            })
+            .collect()
+    }
+}
+
+pub fn window(ui: &imgui::Ui, state: &mut State) {
+    let mut open = state.statement_import.open;
+
+    ui.window(format!(
+        "{}###statement_import",
+        state.locale.tr("import-bank-statement")
+    ))
+    .size([500.0, 350.0], imgui::Condition::FirstUseEver)
+    .position([150., 75.], imgui::Condition::FirstUseEver)
+    .collapsible(false)
+    .opened(&mut open)
+    .build(|| {
+        let locale = &state.locale;
+        let import = &mut state.statement_import;
+
+        ui.text(locale.tr("statement-file"));
+        ui.input_text("##statement_path", &mut import.path)
+            .hint(locale.tr_args("example", &[("value", "statements/2023-05.xml".into())]))
+            .build();
+        ui.same_line();
+        {
+            let _danger_token = ui.begin_disabled(import.path.is_empty());
+            if ui.button(locale.tr("load")) {
+                import.load();
+            }
+        }
+
+        if let Some(error) = &import.error {
+            ui.text_colored(state.settings.palette().error, locale.error(error));
+        }
+
+        if import.transactions.is_empty() {
+            ui.text_disabled(locale.tr("no-transactions"));
+            return;
+        }
+
+        let imported = import.imported().clone();
+        ui.child_window("##transactions")
+            .size([0., -ui.frame_height_with_spacing()])
+            .build(|| {
+                for (i, (transaction, selected)) in import.transactions.iter_mut().enumerate() {
+                    let label = format!(
+                        "{}  {:>9}  {}##{i}",
+                        transaction.date,
+                        locale.language.number(f64::from(transaction.amount), 2),
+                        transaction.description
+                    );
+                    if imported.contains(&transaction.id) {
+                        let _danger_token = ui.begin_disabled(true);
+                        ui.checkbox(format!("{label} ({})", locale.tr("imported")), &mut false);
+                    } else {
+                        ui.checkbox(label, selected);
+                    }
+                }
+            });
+
+        let count = import.transactions.iter().filter(|(_, s)| *s).count();
+        let _danger_token = ui.begin_disabled(count == 0);
+        if ui.button(locale.tr_args("import-selected", &[("count", count.into())])) {
+            for receipt in import.commit() {
+                state.add_receipt(receipt);
+            }
+        }
+    });
+
+    state.statement_import.open = open;
+}
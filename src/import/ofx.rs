@@ -0,0 +1,49 @@
+use super::Transaction;
+use crate::locale::Message;
+use anyhow::Result;
+
+/// Returns the value of `<TAG>` inside `block`, accepting both the SGML
+/// (OFX 1.x, no closing tags) and XML (OFX 2.x) flavours.
+fn tag<'a>(block: &'a str, name: &str) -> Option<&'a str> {
+    let open = format!("<{name}>");
+    let start = block.find(&open)? + open.len();
+    let rest = &block[start..];
+    let end = rest.find('<').unwrap_or(rest.len());
+    Some(rest[..end].trim())
+}
+
+/// Parses every `<STMTTRN>` of an OFX/QFX statement.
+pub fn parse(input: &str) -> Result<Vec<Transaction>> {
+    let mut transactions = Vec::new();
+
+    for block in input.split("<STMTTRN>").skip(1) {
+        let block = block.split("</STMTTRN>").next().unwrap_or(block);
+
+        // DTPOSTED is YYYYMMDD[HHMMSS[.XXX][TZ]]
+        let date: String = tag(block, "DTPOSTED")
+            .unwrap_or_default()
+            .chars()
+            .take(8)
+            .collect();
+        let id = tag(block, "FITID")
+            .ok_or_else(|| Message::new("entry-without-id").arg("date", date.as_str()))?;
+        let amount = tag(block, "TRNAMT").ok_or_else(|| Message::new("entry-without-amount"))?;
+        let description = [tag(block, "NAME"), tag(block, "MEMO")]
+            .into_iter()
+            .flatten()
+            .find(|s| !s.is_empty())
+            .unwrap_or_default();
+
+        transactions.push(Transaction {
+            id: format!("ofx:{id}"),
+            date,
+            amount: amount
+                .replace(',', ".")
+                .parse()
+                .map_err(|_| Message::new("invalid-amount").arg("amount", amount))?,
+            description: description.to_string(),
+        });
+    }
+
+    Ok(transactions)
+}
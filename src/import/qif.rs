@@ -0,0 +1,133 @@
+use super::Transaction;
+use crate::locale::Message;
+use anyhow::Result;
+use std::collections::HashMap;
+
+/// Reads an amount written with either a decimal point or a decimal comma,
+/// e.g. `-1,234.50` or `-1.234,50`. Whichever of `.` and `,` comes last
+/// separates the decimals and the other groups thousands, except that a
+/// separator used alone before three digits groups thousands, as in `1,500`.
+fn amount(value: &str) -> Option<f32> {
+    let decimal = value.rfind(['.', ',']).filter(|&i| {
+        let other = if value[i..].starts_with('.') {
+            ','
+        } else {
+            '.'
+        };
+        let decimals = &value[i + 1..];
+        value.contains(other)
+            || decimals.len() != 3
+            || !decimals.chars().all(|c| c.is_ascii_digit())
+    });
+    let number: String = value
+        .char_indices()
+        .filter_map(|(i, c)| match c {
+            '.' | ',' if Some(i) == decimal => Some('.'),
+            '.' | ',' | '\'' | ' ' => None,
+            c => Some(c),
+        })
+        .collect();
+    number.parse().ok()
+}
+
+/// Parses the bank/cash sections of a Quicken Interchange Format file.
+///
+/// QIF has no transaction ids, so one is derived from the date, amount and
+/// payee, with a counter for otherwise identical entries.
+pub fn parse(input: &str) -> Result<Vec<Transaction>> {
+    let mut transactions = Vec::new();
+    let mut seen: HashMap<String, usize> = HashMap::new();
+
+    let mut date = String::new();
+    let mut amount: Option<f32> = None;
+    let mut payee = String::new();
+    let mut memo = String::new();
+
+    for line in input.lines().map(str::trim) {
+        let (code, value) = match line.char_indices().nth(1) {
+            Some((i, _)) => (&line[..i], line[i..].trim()),
+            None => (line, ""),
+        };
+
+        match code {
+            "D" => date = value.to_string(),
+            "T" | "U" => {
+                amount = Some(
+                    self::amount(value)
+                        .ok_or_else(|| Message::new("invalid-amount").arg("amount", value))?,
+                )
+            }
+            "P" => payee = value.to_string(),
+            "M" => memo = value.to_string(),
+            "^" => {
+                if let Some(amount) = amount.take() {
+                    let description = if payee.is_empty() {
+                        memo.clone()
+                    } else {
+                        payee.clone()
+                    };
+                    let key = format!("{date}:{amount:.2}:{description}");
+                    let n = seen.entry(key.clone()).or_default();
+                    *n += 1;
+
+                    transactions.push(Transaction {
+                        id: format!("qif:{key}:{n}"),
+                        date: date.clone(),
+                        amount,
+                        description,
+                    });
+                }
+                date.clear();
+                payee.clear();
+                memo.clear();
+            }
+            _ => {}
+        }
+    }
+
+    Ok(transactions)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn amounts() {
+        for (value, expected) in [
+            ("-12.50", -12.5),
+            ("-12,50", -12.5),
+            ("1,234.56", 1234.56),
+            ("1.234,56", 1234.56),
+            ("1 234,56", 1234.56),
+            ("-7", -7.),
+            ("1,500", 1500.),
+            ("-1,000", -1000.),
+            ("1.500", 1500.),
+            ("1,000,000", 1_000_000.),
+            ("1,000.00", 1000.),
+            ("1,5", 1.5),
+        ] {
+            assert_eq!(amount(value), Some(expected), "{value}");
+        }
+        assert_eq!(amount("twelve"), None);
+    }
+
+    #[test]
+    fn decimal_comma() {
+        let input =
+            "!Type:Bank\nD18.03.2024\nT-12,50\nPK-Market\n^\nD19.03.2024\nT1.200,00\nMSalary\n^\n";
+        let transactions = parse(input).unwrap();
+        let parsed: Vec<(&str, f32, &str)> = transactions
+            .iter()
+            .map(|t| (t.date.as_str(), t.amount, t.description.as_str()))
+            .collect();
+        assert_eq!(
+            parsed,
+            [
+                ("18.03.2024", -12.5, "K-Market"),
+                ("19.03.2024", 1200., "Salary")
+            ]
+        );
+    }
+}
@@ -0,0 +1,75 @@
+use crate::command::{self, Action};
+use crate::State;
+use imgui::{Condition, Window};
+
+/// Where a panel goes in the default layout, as `[x, y, width, height]`
+/// fractions of the display. The layout was drawn for an 800×500 window.
+pub type Rect = [f32; 4];
+
+pub const PARTICIPANTS: Rect = [10. / 800., 30. / 500., 250. / 800., 360. / 500.];
+pub const ADD_PARTICIPANT: Rect = [10. / 800., 400. / 500., 250. / 800., 90. / 500.];
+pub const EXPORTING: Rect = [270. / 800., 30. / 500., 200. / 800., 200. / 500.];
+pub const IMPORTING: Rect = [270. / 800., 240. / 500., 200. / 800., 125. / 500.];
+pub const RECEIPTS: Rect = [480. / 800., 30. / 500., 310. / 800., 230. / 500.];
+pub const ADD_RECEIPT: Rect = [480. / 800., 270. / 500., 310. / 800., 220. / 500.];
+
+/// The panels keep wherever the user moved, resized or docked them, which
+/// imgui remembers in its ini file. Resetting lays them out afresh for the
+/// current window size.
+#[derive(Default)]
+pub struct Layout {
+    reset: bool,
+}
+
+impl Layout {
+    /// Sizes and positions `window` according to `rect`, only on its first
+    /// appearance unless the layout is being reset.
+    pub fn place<'ui, 'a, L: AsRef<str>>(
+        &self,
+        ui: &imgui::Ui,
+        window: Window<'ui, 'a, L>,
+        rect: Rect,
+    ) -> Window<'ui, 'a, L> {
+        let condition = if self.reset {
+            Condition::Always
+        } else {
+            Condition::FirstUseEver
+        };
+        let [width, height] = ui.io().display_size;
+        window
+            .position([rect[0] * width, rect[1] * height], condition)
+            .size([rect[2] * width, rect[3] * height], condition)
+    }
+
+    /// Lays the panels out afresh on the next frame.
+    pub fn reset(&mut self) {
+        self.reset = true;
+    }
+
+    /// Called once every panel has been placed.
+    pub fn end_frame(&mut self) {
+        self.reset = false;
+    }
+}
+
+pub fn menu(ui: &imgui::Ui, state: &mut State) {
+    ui.menu(state.locale.tr("view-menu"), || {
+        if ui
+            .menu_item_config(state.locale.tr("command-palette"))
+            .shortcut(command::palette_shortcut())
+            .build()
+        {
+            state.commands.open_palette();
+        }
+        ui.separator();
+        for action in [Action::ResetLayout, Action::Preferences] {
+            if ui
+                .menu_item_config(state.locale.tr(action.name()))
+                .shortcut(action.shortcut().map(|c| c.label()).unwrap_or_default())
+                .build()
+            {
+                action.run(state);
+            }
+        }
+    });
+}
@@ -0,0 +1,1273 @@
+use command::{Focus, Reveal};
+use serde::{Deserialize, Serialize};
+use std::cell::RefCell;
+use std::collections::HashMap;
+use std::rc::Rc;
+use uuid::Uuid;
+
+mod accounting;
+mod command;
+mod file_format;
+mod filter;
+mod fuzzy;
+mod import;
+mod layout;
+mod list;
+mod locale;
+mod matrix;
+mod payment;
+#[cfg(test)]
+mod property_tests;
+mod receipt_text;
+mod recurring;
+mod settings;
+mod shares;
+mod split;
+mod splitwise;
+mod statistics;
+mod support;
+mod theme;
+#[cfg(test)]
+mod ui_tests;
+mod workspace;
+
+#[derive(Default, Clone, Debug, Serialize, Deserialize)]
+pub struct Participant {
+    pub id: Uuid,
+    pub name: String,
+    #[serde(default)]
+    credit_account: String,
+    #[serde(default)]
+    debit_account: String,
+    #[serde(default)]
+    iban: String,
+    #[serde(default)]
+    bic: String,
+}
+
+impl Participant {
+    pub fn new(name: String) -> Self {
+        Self {
+            id: Uuid::new_v4(),
+            name,
+            credit_account: String::new(),
+            debit_account: String::new(),
+            iban: String::new(),
+            bic: String::new(),
+        }
+    }
+
+    pub fn share(&self, state: &State) -> f32 {
+        state.shares().sum(self.id)
+    }
+}
+
+#[derive(Default, Clone, Debug, Serialize, Deserialize)]
+struct Item {
+    label: String,
+    quantity: f32,
+    discount: f32,
+    total: f32,
+}
+
+/// Categories offered for receipts, others can come in through imports.
+const CATEGORIES: [&str; 8] = [
+    "General",
+    "Groceries",
+    "Dining",
+    "Transport",
+    "Lodging",
+    "Utilities",
+    "Entertainment",
+    "Other",
+];
+
+pub fn default_category() -> String {
+    CATEGORIES[0].to_string()
+}
+
+#[derive(Default, Clone, Debug, Serialize, Deserialize)]
+pub struct Receipt {
+    label: String,
+    total: f32,
+    id: Uuid,
+    exclusion: bool,
+    #[serde(default)]
+    items: Vec<Item>,
+    #[serde(default)]
+    pub payer: Option<Uuid>,
+    #[serde(default)]
+    date: Option<chrono::NaiveDate>,
+    #[serde(default = "default_category")]
+    category: String,
+    #[serde(default)]
+    tags: Vec<String>,
+}
+
+impl Receipt {
+    pub fn new(label: String, total: f32, exlusion: bool) -> Self {
+        Self {
+            label,
+            total,
+            id: Uuid::new_v4(),
+            exclusion: exlusion,
+            items: Vec::new(),
+            payer: None,
+            date: None,
+            category: default_category(),
+            tags: Vec::new(),
+        }
+    }
+
+    pub fn total(&self, state: &State) -> f32 {
+        state.shares().total_of(self)
+    }
+}
+
+/// The data of one group of people splitting costs, e.g. "Flat 2B".
+#[derive(Default, Clone, Debug, Serialize, Deserialize)]
+struct Session {
+    name: String,
+    participants: Vec<Participant>,
+    share_map: HashMap<Uuid, Vec<Uuid>>,
+    receipts: Vec<Receipt>,
+    exclusions: HashMap<Uuid, Vec<Receipt>>,
+    #[serde(default)]
+    payments: Vec<payment::ledger::Payment>,
+    #[serde(default)]
+    recurring: Vec<recurring::Template>,
+}
+
+impl Session {
+    fn new(name: String) -> Self {
+        Self {
+            name,
+            ..Default::default()
+        }
+    }
+}
+
+/// A participant's name and total with their share of each receipt, by
+/// label, as exported.
+type ShareLine<'a> = (&'a str, f32, Vec<(&'a str, f32)>);
+
+/// Rows a list shows, as indices into the session, with what the search
+/// matched in each.
+type Found = Rc<Vec<(usize, fuzzy::Match)>>;
+
+/// Figures derived from the session, computed when first asked for after
+/// it changes rather than by every window and row asking.
+#[derive(Default)]
+struct Figures {
+    shares: Option<Rc<shares::Shares>>,
+    balances: Option<Rc<HashMap<Uuid, i64>>>,
+    /// The participants and receipts found, with the search they were
+    /// found with, as the search changes without the session changing
+    participants_found: Option<(String, Found)>,
+    receipts_found: Option<(String, Found)>,
+}
+
+#[derive(Default)]
+pub struct State {
+    session_name: String,
+    pub participants: Vec<Participant>,
+    share_map: HashMap<Uuid, Vec<Uuid>>,
+    pub receipts: Vec<Receipt>,
+    exclusions: HashMap<Uuid, Vec<Receipt>>,
+    recurring: Vec<recurring::Template>,
+
+    tmp_name: String,
+    tmp_label: String,
+    tmp_total: f32,
+
+    r_tmp_label: String,
+    r_tmp_total: f32,
+
+    tmp_date: String,
+    /// `None` until picked, meaning the default category
+    tmp_category: Option<String>,
+    tmp_tag: String,
+    filter: filter::Filter,
+
+    statement_import: import::StatementImport,
+    paste_receipt: receipt_text::PasteReceipt,
+    eml_import: import::eml::EmlImport,
+    accounting_export: accounting::AccountingExport,
+    splitwise: splitwise::Splitwise,
+    settlement: payment::Settlement,
+    statements: payment::statement::Statements,
+    statistics: statistics::Statistics,
+    matrix: matrix::Matrix,
+    layout: layout::Layout,
+    settings: settings::Settings,
+    locale: locale::Locale,
+    preferences: settings::Preferences,
+    payments: Vec<payment::ledger::Payment>,
+    payment_form: payment::ledger::PaymentForm,
+    recurring_ui: recurring::RecurringUi,
+
+    workspace: workspace::Workspace,
+    workspace_ui: workspace::WorkspaceUi,
+    commands: command::Commands,
+    participant_list: list::List,
+    receipt_list: list::List,
+    figures: RefCell<Figures>,
+}
+
+impl From<Session> for State {
+    fn from(session: Session) -> Self {
+        let mut state = State::default();
+        state.load_session(session);
+        state
+    }
+}
+
+impl State {
+    fn session(&self) -> Session {
+        Session {
+            name: self.session_name.clone(),
+            participants: self.participants.clone(),
+            share_map: self.share_map.clone(),
+            receipts: self.receipts.clone(),
+            exclusions: self.exclusions.clone(),
+            payments: self.payments.clone(),
+            recurring: self.recurring.clone(),
+        }
+    }
+
+    /// Drops the derived figures. Called by everything that changes the
+    /// participants, receipts, exclusions, payments or the filter.
+    pub fn session_changed(&self) {
+        self.figures.take();
+    }
+
+    fn load_session(&mut self, session: Session) {
+        self.session_changed();
+        self.session_name = session.name;
+        self.participants = session.participants;
+        self.share_map = session.share_map;
+        self.receipts = session.receipts;
+        self.exclusions = session.exclusions;
+        self.payments = session.payments;
+        self.recurring = session.recurring;
+    }
+
+    pub fn add_participant(&mut self, participant: Participant) {
+        self.session_changed();
+        self.workspace.remember(&participant);
+        self.share_map
+            .insert(participant.id, self.receipts.iter().map(|r| r.id).collect());
+        self.participants.push(participant);
+    }
+
+    pub fn add_receipt(&mut self, receipt: Receipt) {
+        self.session_changed();
+        if self.settings.auto_share {
+            self.share_map
+                .iter_mut()
+                .for_each(|(_, l)| l.push(receipt.id));
+        }
+        self.receipts.push(receipt);
+    }
+
+    /// Adds `item` as excluded from the receipt `parent`, to be shared
+    /// separately.
+    fn exclude(&mut self, parent: Uuid, item: Receipt) {
+        self.exclusions
+            .entry(parent)
+            .or_default()
+            .push(item.clone());
+        self.add_receipt(item);
+    }
+
+    fn remove_exclusion(&mut self, id: &Uuid) {
+        self.session_changed();
+        self.exclusions.remove(id);
+    }
+
+    fn remove_participant(&mut self, index: usize) {
+        self.session_changed();
+        let participant = self.participants.remove(index);
+        self.share_map.remove(&participant.id);
+    }
+
+    fn remove_receipt(&mut self, index: usize) {
+        self.session_changed();
+        let receipt = self.receipts.remove(index);
+        if receipt.exclusion {
+            self.remove_exclusion(&receipt.id)
+        }
+    }
+
+    /// Adds or removes `receipt` from what `participant` shares.
+    fn set_shared(&mut self, participant: Uuid, receipt: Uuid, shared: bool) {
+        self.session_changed();
+        let list = self.share_map.entry(participant).or_default();
+        if !shared {
+            list.retain(|id| *id != receipt);
+        } else if !list.contains(&receipt) {
+            list.push(receipt);
+        }
+    }
+
+    /// How the receipts divide between the participants, worked out again
+    /// only after the session changes.
+    fn shares(&self) -> Rc<shares::Shares> {
+        if let Some(shares) = &self.figures.borrow().shares {
+            return shares.clone();
+        }
+        let shares = Rc::new(shares::Shares::new(self));
+        self.figures.borrow_mut().shares = Some(shares.clone());
+        shares
+    }
+
+    /// Participants sharing `receipt`, in the order they were added.
+    fn sharers(&self, receipt: &Receipt) -> Vec<&Participant> {
+        let shares = self.shares();
+        let sharers = shares.sharers(receipt.id);
+        self.participants
+            .iter()
+            .filter(|p| sharers.contains(&p.id))
+            .collect()
+    }
+
+    /// The receipt `item` was excluded from, if it is an excluded item.
+    fn parent_of(&self, item: &Receipt) -> Option<&Receipt> {
+        let shares = self.shares();
+        shares
+            .parent(item.id)
+            .and_then(|parent| shares.index(parent))
+            .and_then(|i| self.receipts.get(i))
+    }
+
+    /// Who paid for `receipt`. Excluded items are paid by whoever paid the
+    /// receipt they were excluded from.
+    fn payer_of(&self, receipt: &Receipt) -> Option<Uuid> {
+        receipt
+            .payer
+            .or_else(|| self.parent_of(receipt).and_then(|parent| parent.payer))
+    }
+
+    /// The offered categories followed by any others receipts use.
+    fn categories(&self) -> Vec<String> {
+        let mut categories: Vec<String> = CATEGORIES.iter().map(|c| c.to_string()).collect();
+        for receipt in self.receipts.iter() {
+            if !categories.contains(&receipt.category) {
+                categories.push(receipt.category.clone());
+            }
+        }
+        categories
+    }
+
+    fn tags(&self) -> Vec<String> {
+        let mut tags: Vec<String> = self
+            .receipts
+            .iter()
+            .flat_map(|r| r.tags.iter().cloned())
+            .collect();
+        tags.sort();
+        tags.dedup();
+        tags
+    }
+
+    /// What every participant has paid minus what they owe, see
+    /// `payment::balances`.
+    pub fn balances(&self) -> Rc<HashMap<Uuid, i64>> {
+        if let Some(balances) = &self.figures.borrow().balances {
+            return balances.clone();
+        }
+        let balances = Rc::new(payment::balances(self));
+        self.figures.borrow_mut().balances = Some(balances.clone());
+        balances
+    }
+
+    pub fn total(&self) -> f32 {
+        self.shares().total()
+    }
+
+    /// What `slot` holds if it was found with `search`, otherwise what `find`
+    /// finds, kept in `slot`.
+    fn found(
+        &self,
+        slot: fn(&mut Figures) -> &mut Option<(String, Found)>,
+        search: &str,
+        find: impl FnOnce() -> Vec<(usize, fuzzy::Match)>,
+    ) -> Found {
+        if let Some((found_with, found)) = slot(&mut self.figures.borrow_mut()) {
+            if found_with == search {
+                return found.clone();
+            }
+        }
+        // Finding asks for the shares, which borrows the figures again
+        let found = Rc::new(find());
+        *slot(&mut self.figures.borrow_mut()) = Some((search.to_string(), found.clone()));
+        found
+    }
+
+    /// The participants the search finds.
+    fn participants_found(&self) -> Found {
+        let list = &self.participant_list;
+        self.found(
+            |f| &mut f.participants_found,
+            &list.search,
+            || {
+                self.participants
+                    .iter()
+                    .enumerate()
+                    .filter_map(|(i, p)| {
+                        let share = f64::from(p.share(self));
+                        list.find(&p.name, share).map(|m| (i, m))
+                    })
+                    .collect()
+            },
+        )
+    }
+
+    /// The receipts the filter lets through and the search finds.
+    fn receipts_found(&self) -> Found {
+        let list = &self.receipt_list;
+        self.found(
+            |f| &mut f.receipts_found,
+            &list.search,
+            || {
+                self.receipts
+                    .iter()
+                    .enumerate()
+                    .filter(|(_, r)| self.filter.matches(self, r))
+                    .filter_map(|(i, r)| {
+                        let total = f64::from(r.total(self));
+                        list.find(&r.label, total).map(|m| (i, m))
+                    })
+                    .collect()
+            },
+        )
+    }
+
+    /// Every participant with a share, in the order they were added, with
+    /// their total and the receipts they share, by label.
+    fn share_lines(&self) -> Vec<ShareLine<'_>> {
+        let shares = self.shares();
+        self.participants
+            .iter()
+            .filter(|p| !shares.of(p.id).is_empty())
+            .map(|p| {
+                let receipts = shares
+                    .of(p.id)
+                    .iter()
+                    .map(|(i, share)| (self.receipts[*i].label.as_str(), *share))
+                    .collect();
+                (p.name.as_str(), shares.sum(p.id), receipts)
+            })
+            .collect()
+    }
+
+    fn export_csv(&self) -> String {
+        let lines = self.share_lines();
+        let language = self.settings.language;
+        let money = |amount: f32| {
+            language.money(
+                &language.plain_number(f64::from(amount), 2),
+                &self.settings.currency_symbol,
+            )
+        };
+        let mut writer = csv::WriterBuilder::new()
+            .delimiter(language.csv_delimiter())
+            .from_writer(Vec::new());
+
+        let _ = writer.write_record([
+            self.locale.tr("name"),
+            self.locale.tr("total"),
+            self.locale.tr("receipt"),
+        ]);
+
+        for (name, total, receipts) in lines.iter() {
+            let _ = writer.write_record([*name, &money(*total), ""]);
+            for (label, total) in receipts.iter() {
+                let _ = writer.write_record(["", &money(*total), label]);
+            }
+        }
+
+        let _ = writer.write_record([&self.locale.tr("total"), &money(self.total()), ""]);
+        let s = writer
+            .into_inner()
+            .map(|bytes| String::from_utf8_lossy(&bytes).into_owned())
+            .unwrap_or_default();
+        println!("{s}");
+        s
+    }
+
+    fn export_txt(&self) -> String {
+        let mut s = String::new();
+        let lines = self.share_lines();
+
+        // Widths are counted in characters, as amounts and names are not
+        // always ASCII
+        fn width(s: &str) -> usize {
+            s.chars().count()
+        }
+
+        fn f(s: &str, l: usize) -> String {
+            if width(s) >= l {
+                s.to_string()
+            } else {
+                let n = l - width(s);
+                s.to_string() + &" ".repeat(n)
+            }
+        }
+
+        let name_heading = self.locale.tr("name");
+        let total_heading = self.locale.tr("total");
+        let receipt_heading = self.locale.tr("receipt");
+
+        let c1 = lines
+            .iter()
+            .map(|(name, _, _)| width(name))
+            .chain([width(&name_heading), width(&total_heading)])
+            .max()
+            .unwrap();
+        let c2 = lines
+            .iter()
+            .flat_map(|(_, total, receipts)| {
+                [*total].into_iter().chain(receipts.iter().map(|r| r.1))
+            })
+            .map(|x| width(&self.settings.money(f64::from(x))))
+            .chain([
+                width(&total_heading),
+                width(&self.settings.money(f64::from(self.total()))),
+            ])
+            .max()
+            .unwrap();
+        let c3 = lines
+            .iter()
+            .flat_map(|(_, _, receipts)| receipts.iter())
+            .map(|(label, _)| width(label))
+            .chain([width(&receipt_heading)])
+            .max()
+            .unwrap();
+
+        s += &format!(
+            "{}\n\n",
+            self.locale.tr_args(
+                "report-title",
+                &[
+                    ("session", self.session_name.as_str().into()),
+                    (
+                        "date",
+                        self.settings.date(chrono::Local::now().date_naive()).into()
+                    ),
+                ],
+            )
+        );
+        s += &format!(
+            "{}   {}   {}\n",
+            f(&name_heading, c1),
+            f(&total_heading, c2),
+            f(&receipt_heading, c3)
+        );
+        s += &format!("{}\n", "-".repeat(9 + c1 + c2 + c3));
+
+        for (name, total, receipts) in lines.iter() {
+            s += &format!(
+                "{}   {}   {}\n",
+                f(name, c1),
+                f(&self.settings.money(f64::from(*total)), c2),
+                f("", c3)
+            );
+
+            for (label, total) in receipts.iter() {
+                s += &format!(
+                    "{} > {}   {}\n",
+                    f("", c1),
+                    f(&self.settings.money(f64::from(*total)), c2),
+                    f(label, c3)
+                );
+            }
+        }
+
+        s += &format!("{}\n", "-".repeat(9 + c1 + c2 + c3));
+        s += &format!(
+            "{}   {}   {}",
+            f(&total_heading, c1),
+            f(&self.settings.money(f64::from(self.total())), c2),
+            f("", c3)
+        );
+
+        println!("{s}");
+        s
+    }
+}
+
+/// Opens the window titled after `title`'s file name and runs the app until
+/// it is closed.
+pub fn run(title: &str) {
+    let settings = settings::Settings::load();
+    let mut state = State {
+        locale: locale::Locale::new(settings.language),
+        settings,
+        ..Default::default()
+    };
+    state.load_session(state.workspace.sessions[0].clone());
+    if let Err(e) = state.load_workspace() {
+        eprintln!("Failed to load workspace: {e}");
+        state.workspace_ui.message = Some(state.locale.error(&e));
+    }
+    let last = state.settings.last_session.clone();
+    if let Some(i) = state
+        .workspace
+        .sessions
+        .iter()
+        .position(|s| Some(&s.name) == last.as_ref())
+    {
+        if i != state.workspace.active {
+            state.switch_session(i);
+        }
+    }
+
+    if !state.settings.font_path.is_empty() {
+        match settings::read_font(&state.settings.font_path) {
+            Ok(data) => state.preferences.extra_font = Some(data),
+            Err(e) => eprintln!("Failed to load font: {e}"),
+        }
+    }
+
+    let mut system = support::init(title, state.settings.window_size);
+    let base_style = *system.imgui.style();
+    state.preferences.base_style = Some(base_style);
+    *system.imgui.style_mut() = state.settings.style(&base_style);
+    if let Err(e) = support::fonts::reload(
+        &mut system.imgui,
+        &mut system.renderer,
+        state.settings.font_pixels(),
+        state.preferences.extra_font.as_deref(),
+    ) {
+        eprintln!("Failed to load fonts: {e}");
+    }
+
+    system.main_loop(move |_, ui, textures, changes, chords| {
+        frame(ui, &mut state, textures, changes, chords)
+    });
+}
+
+/// The whole interface drawn without a window, a frame at a time, so that
+/// benchmarks time what a real frame does.
+pub struct HeadlessApp {
+    headless: support::headless::Headless,
+    pub state: State,
+}
+
+impl HeadlessApp {
+    /// Draws `state` at its window size, which is then not saved as
+    /// changed, styled by its settings.
+    pub fn new(state: State) -> Self {
+        let [width, height] = state.settings.window_size;
+        let mut headless = support::headless::Headless::new([width as f32, height as f32]);
+        let base_style = *headless.imgui.style();
+        *headless.imgui.style_mut() = state.settings.style(&base_style);
+        Self { headless, state }
+    }
+
+    /// Builds a frame, returning the summary of what it draws.
+    pub fn frame(&mut self) -> String {
+        let state = &mut self.state;
+        self.headless
+            .frame(|ui, textures, changes, chords| frame(ui, state, textures, changes, chords))
+    }
+}
+
+/// Builds one frame of the whole interface.
+fn frame(
+    ui: &imgui::Ui,
+    state: &mut State,
+    textures: &mut support::Textures,
+    changes: &mut support::Changes,
+    chords: &[support::Chord],
+) {
+    command::shortcuts(state, chords);
+
+    // Panels float over the OS window and can be docked to its edges
+    ui.dockspace_over_main_viewport();
+    ui.main_menu_bar(|| {
+        workspace::menu(ui, state);
+        layout::menu(ui, state);
+        workspace::status(ui, state);
+    });
+
+    state
+        .layout
+        .place(
+            ui,
+            ui.window(format!(
+                "{}###participants",
+                state.locale.tr("participants")
+            )),
+            layout::PARTICIPANTS,
+        )
+        .collapsible(false)
+        .focused(state.commands.reveals_participant())
+        .build(|| {
+            state.participant_list.search_box(
+                ui,
+                "##participant_search",
+                state.locale.tr("search-participants"),
+            );
+            if let Some(Reveal::Participant(id)) = state.commands.reveal {
+                state.participant_list.search.clear();
+                state.participant_list.open(id);
+            }
+
+            let balances = state.balances();
+            let visible = state.participants_found();
+            let open: Vec<bool> = visible
+                .iter()
+                .map(|(i, _)| state.participant_list.is_open(state.participants[*i].id))
+                .collect();
+
+            let mut remove = None;
+            list::rows(ui, &open, |n| {
+                let (row_num, matched) = &visible[n];
+                let row_num = *row_num as i32;
+                if let Some(participant) = &state.participants.get(row_num as usize) {
+                    let id = participant.id;
+                    let balance = balances.get(&id).copied().unwrap_or_default();
+                    let palette = state.settings.palette();
+                    let standing = match balance {
+                        b if b < 0 => Some((
+                            palette.debt,
+                            state.locale.tr_args(
+                                "still-owes",
+                                &[("amount", state.settings.money(-b as f64 / 100.).into())],
+                            ),
+                        )),
+                        b if b > 0 => Some((
+                            palette.credit,
+                            state.locale.tr_args(
+                                "is-owed",
+                                &[("amount", state.settings.money(b as f64 / 100.).into())],
+                            ),
+                        )),
+                        _ => None,
+                    };
+                    if state.commands.reveal == Some(Reveal::Participant(id)) {
+                        ui.set_scroll_here_y();
+                    }
+                    let label = format!(
+                        "{}: {}",
+                        participant.name,
+                        state.settings.money(f64::from(participant.share(state)))
+                    );
+                    let node = state.participant_list.node(ui, id, &label, matched);
+                    if let Some((color, standing)) = standing {
+                        ui.same_line();
+                        ui.text_colored(color, format!("({standing})"));
+                    }
+                    if let Some(_node) = node {
+                        ui.tree_node_config(format!(
+                            "{}##manage_receipts_{row_num}",
+                            state.locale.tr("manage-receipts")
+                        ))
+                        .build(|| {
+                            if state.receipts.is_empty() {
+                                ui.text_disabled(state.locale.tr("no-receipts-to-share"));
+                            } else {
+                                ui.text(state.locale.tr("partaking-in"));
+                                ui.text_disabled("------------------");
+                                let mut toggle = None;
+                                for receipt in state.receipts.iter() {
+                                    let selected = state.share_map[&id].contains(&receipt.id);
+
+                                    if ui
+                                        .selectable_config(format!(
+                                            "{} ({})##{row_num}",
+                                            receipt.label,
+                                            state.locale.tr(if selected {
+                                                "partake"
+                                            } else {
+                                                "ignore"
+                                            })
+                                        ))
+                                        .allow_double_click(false)
+                                        .selected(selected)
+                                        .build()
+                                    {
+                                        toggle = Some((receipt.id, !selected));
+                                    }
+                                }
+                                if let Some((receipt, shared)) = toggle {
+                                    state.set_shared(id, receipt, shared);
+                                }
+                                ui.text_disabled("------------------");
+                            }
+                        });
+
+                        ui.tree_node_config(format!(
+                            "{}##bank_details_{row_num}",
+                            state.locale.tr("bank-details")
+                        ))
+                        .build(|| {
+                            let locale = &state.locale;
+                            let p = &mut state.participants[row_num as usize];
+                            ui.text_disabled("IBAN");
+                            ui.input_text(format!("##iban_{row_num}"), &mut p.iban)
+                                .hint(locale.tr_args(
+                                    "example",
+                                    &[("value", "FI21 1234 5600 0007 85".into())],
+                                ))
+                                .build();
+                            if let Err(e) = payment::iban::validate_iban(&p.iban) {
+                                if !p.iban.is_empty() {
+                                    ui.text_colored(
+                                        state.settings.palette().error,
+                                        locale.error(&e),
+                                    );
+                                }
+                            }
+
+                            ui.text_disabled(locale.tr("bic-optional"));
+                            ui.input_text(format!("##bic_{row_num}"), &mut p.bic)
+                                .hint(locale.tr_args("example", &[("value", "NDEAFIHH".into())]))
+                                .build();
+                            if let Err(e) = payment::iban::validate_bic(&p.bic) {
+                                if !p.bic.is_empty() {
+                                    ui.text_colored(
+                                        state.settings.palette().error,
+                                        locale.error(&e),
+                                    );
+                                }
+                            }
+                        });
+
+                        if ui.button(state.locale.tr("remove")) {
+                            remove = Some(row_num as usize);
+                        }
+                    }
+                }
+            });
+            // Only once the rows are drawn, as they were counted before
+            if let Some(row_num) = remove {
+                state.remove_participant(row_num);
+            }
+            if state.commands.reveals_participant() {
+                state.commands.reveal = None;
+            }
+        });
+
+    state
+        .layout
+        .place(
+            ui,
+            ui.window(format!(
+                "{}###add_participant",
+                state.locale.tr("add-participant")
+            )),
+            layout::ADD_PARTICIPANT,
+        )
+        .collapsible(false)
+        .focused(state.commands.focuses(Focus::ParticipantName))
+        .build(|| {
+            ui.text(state.locale.tr("participant-name"));
+            state.commands.focus_here(ui, Focus::ParticipantName);
+            let enter = ui
+                .input_text("##add_participant", &mut state.tmp_name)
+                .hint(state.locale.tr("participant-name-hint"))
+                .enter_returns_true(true)
+                .build();
+
+            {
+                let _danger_token = ui.begin_disabled(state.tmp_name.is_empty());
+                if (ui.button(state.locale.tr("add")) || enter) && !state.tmp_name.is_empty() {
+                    state.add_participant(Participant::new(state.tmp_name.clone()));
+                    state.tmp_name = String::new();
+                    // Ready for the next one
+                    state.commands.focus = Some(Focus::ParticipantName);
+                }
+            }
+
+            ui.same_line();
+            if ui.button(state.locale.tr("from-contacts")) {
+                ui.open_popup("##contacts");
+            }
+            workspace::contacts_popup(ui, state);
+        });
+
+    state
+        .layout
+        .place(
+            ui,
+            ui.window(format!("{}###receipts", state.locale.tr("receipts"))),
+            layout::RECEIPTS,
+        )
+        .collapsible(false)
+        .focused(state.commands.reveals_receipt() || state.commands.focuses(Focus::Search))
+        .build(|| {
+            state.commands.focus_here(ui, Focus::Search);
+            state.receipt_list.search_box(
+                ui,
+                "##receipt_search",
+                state.locale.tr("search-receipts"),
+            );
+            if let Some(Reveal::Receipt(id)) = state.commands.reveal {
+                state.receipt_list.search.clear();
+                state.receipt_list.open(id);
+            }
+
+            let filter = state.locale.tr(if state.filter.is_active() {
+                "filter-active"
+            } else {
+                "filter"
+            });
+            ui.tree_node_config(format!("{filter}###filter"))
+                .build(|| filter::edit(ui, state));
+            if ui.small_button(state.locale.tr("assignment-matrix")) {
+                state.matrix.open = true;
+            }
+
+            let visible = state.receipts_found();
+            let open: Vec<bool> = visible
+                .iter()
+                .map(|(i, _)| state.receipt_list.is_open(state.receipts[*i].id))
+                .collect();
+
+            let mut remove = None;
+            list::rows(ui, &open, |n| {
+                let (row_num, matched) = &visible[n];
+                let row_num = *row_num as i32;
+                if let Some(receipt) = &state.receipts.get(row_num as usize) {
+                    let id = receipt.id;
+                    let total = receipt.total(state);
+                    let exclusion = receipt.exclusion;
+                    let items = receipt.items.clone();
+                    let exclusions: Vec<(String, f32)> = state
+                        .exclusions
+                        .iter()
+                        .find(|(id, _)| **id == receipt.id)
+                        .map(|(_, e)| {
+                            e.iter()
+                                .map(|r| (r.label.clone(), r.total(state)))
+                                .collect()
+                        })
+                        .unwrap_or_default();
+
+                    if state.commands.reveal == Some(Reveal::Receipt(id)) {
+                        ui.set_scroll_here_y();
+                    }
+                    let label = format!(
+                        "{}: {}",
+                        receipt.label,
+                        state.settings.money(f64::from(total))
+                    );
+                    if let Some(_node) = state.receipt_list.node(ui, id, &label, matched) {
+                        if !exclusion {
+                            let mut names = vec![state.locale.tr("nobody")];
+                            names.extend(state.participants.iter().map(|p| p.name.clone()));
+                            let mut payer = state.receipts[row_num as usize]
+                                .payer
+                                .and_then(|id| state.participants.iter().position(|p| p.id == id))
+                                .map_or(0, |i| i + 1);
+                            if ui.combo_simple_string(
+                                format!("{}##payer_{row_num}", state.locale.tr("paid-by")),
+                                &mut payer,
+                                &names,
+                            ) {
+                                state.receipts[row_num as usize].payer =
+                                    payer.checked_sub(1).map(|i| state.participants[i].id);
+                                state.session_changed();
+                            }
+
+                            ui.tree_node_config(format!(
+                                "{}##details_{row_num}",
+                                state.locale.tr("details")
+                            ))
+                            .build(|| filter::details(ui, state, row_num as usize));
+
+                            ui.tree_node_config(format!(
+                                "{}##item_exclusions_{row_num}",
+                                state.locale.tr_args(
+                                    "item-exclusions",
+                                    &[("count", exclusions.len().into())]
+                                )
+                            ))
+                            .build(|| {
+                                for (label, total) in exclusions {
+                                    ui.text_colored(
+                                        state.settings.palette().exclusion,
+                                        format!(
+                                            "- {} ({})",
+                                            state.settings.number(f64::from(total)),
+                                            label
+                                        ),
+                                    );
+                                }
+                            });
+
+                            ui.tree_node_config(format!(
+                                "{}##manage_exclusions_{row_num}",
+                                state.locale.tr("manage-exclusions")
+                            ))
+                            .build(|| {
+                                ui.text_disabled("----------------------");
+                                for item in items.iter() {
+                                    if ui.selectable(format!(
+                                        "{} ({})##{row_num}",
+                                        item.label,
+                                        state.settings.number(f64::from(item.total))
+                                    )) {
+                                        state.r_tmp_label = item.label.clone();
+                                        state.r_tmp_total = item.total;
+                                    }
+                                }
+                                if !items.is_empty() {
+                                    ui.text_disabled("----------------------");
+                                }
+                                ui.text_disabled(state.locale.tr("item-name"));
+                                let mut enter = ui
+                                    .input_text("##item_label", &mut state.r_tmp_label)
+                                    .hint(state.locale.tr("item-name-hint"))
+                                    .enter_returns_true(true)
+                                    .build();
+
+                                ui.text_disabled(state.locale.tr("item-cost"));
+                                enter |= ui
+                                    .input_float("##item_total", &mut state.r_tmp_total)
+                                    .enter_returns_true(true)
+                                    .build();
+
+                                if ui.button(state.locale.tr("exclude"))
+                                    || enter && !state.r_tmp_label.is_empty()
+                                {
+                                    let receipt = Receipt::new(
+                                        state.r_tmp_label.clone(),
+                                        state.r_tmp_total,
+                                        true,
+                                    );
+                                    state.exclude(id, receipt);
+
+                                    state.r_tmp_label = String::new();
+                                    state.r_tmp_total = 0.;
+                                }
+                                ui.text_disabled("----------------------");
+                            });
+                        } else {
+                            ui.text_colored(
+                                state.settings.palette().exclusion,
+                                state.locale.tr("item-excluded"),
+                            );
+                        }
+
+                        if ui.button(state.locale.tr("remove")) {
+                            remove = Some(row_num as usize);
+                        }
+                    }
+                }
+            });
+            if let Some(row_num) = remove {
+                state.remove_receipt(row_num);
+            }
+            if state.commands.reveals_receipt() {
+                state.commands.reveal = None;
+            }
+        });
+
+    state
+        .layout
+        .place(
+            ui,
+            ui.window(format!("{}###add_receipt", state.locale.tr("add-receipt"))),
+            layout::ADD_RECEIPT,
+        )
+        .collapsible(false)
+        .focused(state.commands.focuses(Focus::ReceiptLabel))
+        .build(|| {
+            ui.text(state.locale.tr("receipt-label"));
+            state.commands.focus_here(ui, Focus::ReceiptLabel);
+            let mut enter = ui
+                .input_text("##receipt_label", &mut state.tmp_label)
+                .hint(state.locale.tr("receipt-label-hint"))
+                .enter_returns_true(true)
+                .build();
+
+            ui.text(state.locale.tr("receipt-total"));
+            enter |= ui
+                .input_float("##receipt_total", &mut state.tmp_total)
+                .enter_returns_true(true)
+                .build();
+
+            ui.tree_node_config(format!("{}##options", state.locale.tr("options")))
+                .build(|| {
+                    let categories = state.categories();
+                    let category = state
+                        .tmp_category
+                        .as_ref()
+                        .unwrap_or(&state.settings.default_category);
+                    let mut index = categories.iter().position(|c| c == category).unwrap_or(0);
+                    if ui.combo_simple_string(
+                        format!("{}##new_receipt_category", state.locale.tr("category")),
+                        &mut index,
+                        &state.locale.categories(&categories),
+                    ) {
+                        state.tmp_category = Some(categories[index].clone());
+                    }
+                    ui.input_text(
+                        format!("{}##new_receipt_date", state.locale.tr("date")),
+                        &mut state.tmp_date,
+                    )
+                    .hint(state.locale.tr("date-hint-optional"))
+                    .build();
+
+                    if ui
+                        .selectable_config(state.locale.tr_args(
+                            "auto-share",
+                            &[("enabled", state.settings.auto_share.to_string().into())],
+                        ))
+                        .selected(state.settings.auto_share)
+                        .build()
+                    {
+                        state.settings.auto_share = !state.settings.auto_share;
+                        state.settings_changed();
+                    }
+                });
+
+            {
+                let incomplete = state.tmp_label.is_empty() || state.tmp_total <= 0.;
+                let _danger_token = ui.begin_disabled(incomplete);
+                if (ui.button(state.locale.tr("add")) || enter) && !incomplete {
+                    let mut receipt = Receipt::new(state.tmp_label.clone(), state.tmp_total, false);
+                    receipt.date = locale::parse_date(&state.tmp_date);
+                    receipt.category = state
+                        .tmp_category
+                        .take()
+                        .unwrap_or_else(|| state.settings.default_category.clone());
+                    state.add_receipt(receipt);
+
+                    state.tmp_label = String::new();
+                    state.tmp_total = 0.;
+                    state.commands.focus = Some(Focus::ReceiptLabel);
+                }
+            }
+        });
+
+    state
+        .layout
+        .place(
+            ui,
+            ui.window(format!(
+                "{}###exporting",
+                state.locale.tr("exporting-options")
+            )),
+            layout::EXPORTING,
+        )
+        .collapsible(false)
+        .build(|| {
+            ui.text(state.locale.tr(if state.filter.is_active() {
+                "filtered-total"
+            } else {
+                "current-total"
+            }));
+            ui.text_disabled(state.settings.money(f64::from(state.total())));
+
+            if ui.button(state.locale.tr("export-csv")) {
+                state.export_csv();
+            }
+
+            if ui.button(state.locale.tr("export-txt")) {
+                state.export_txt();
+            }
+
+            if ui.button(state.locale.tr("export-accounting")) {
+                state.accounting_export.open = true;
+            }
+
+            if ui.button(state.locale.tr("settle-up")) {
+                state.settlement.open = true;
+            }
+
+            if ui.button(state.locale.tr("statements")) {
+                state.statements.open = true;
+            }
+
+            if ui.button(state.locale.tr("statistics")) {
+                state.statistics.open = true;
+            }
+        });
+
+    state
+        .layout
+        .place(
+            ui,
+            ui.window(format!(
+                "{}###importing",
+                state.locale.tr("importing-options")
+            )),
+            layout::IMPORTING,
+        )
+        .collapsible(false)
+        .build(|| {
+            if ui.button(state.locale.tr("import-bank-statement")) {
+                state.statement_import.open = true;
+            }
+
+            if ui.button(state.locale.tr("paste-receipt")) {
+                state.paste_receipt.open = true;
+            }
+
+            if ui.button(state.locale.tr("import-e-receipt")) {
+                state.eml_import.open = true;
+            }
+
+            if ui.button(state.locale.tr("splitwise-import-export")) {
+                state.splitwise.open = true;
+            }
+        });
+
+    if state.statement_import.open {
+        import::window(ui, state);
+    }
+
+    if state.paste_receipt.open {
+        receipt_text::window(ui, state);
+    }
+
+    if state.eml_import.open {
+        import::eml::window(ui, state);
+    }
+
+    if state.accounting_export.open {
+        accounting::window(ui, state);
+    }
+
+    if state.splitwise.open {
+        splitwise::window(ui, state);
+    }
+
+    if state.settlement.open {
+        payment::window(ui, state, textures);
+    }
+
+    if state.statements.open {
+        payment::statement::window(ui, state);
+    }
+
+    if state.matrix.open {
+        matrix::window(ui, state);
+    }
+
+    if state.statistics.open {
+        statistics::window(ui, state);
+    }
+
+    if state.recurring_ui.open {
+        recurring::window(ui, state);
+    }
+
+    if state.workspace_ui.totals_open {
+        workspace::totals_window(ui, state);
+    }
+
+    if state.preferences.open {
+        settings::window(ui, state, changes);
+    }
+
+    command::palette(ui, state);
+
+    state.layout.end_frame();
+    state.save_settings(ui, changes);
+}
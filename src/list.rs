@@ -0,0 +1,128 @@
+use crate::fuzzy::{self, Match};
+use imgui::{Condition, ListClipper, StyleColor, TreeNodeFlags, TreeNodeToken};
+use std::collections::HashSet;
+use uuid::Uuid;
+
+/// The search and the expanded rows of a list of participants or receipts.
+#[derive(Default)]
+pub struct List {
+    pub search: String,
+    /// Rows that are expanded, which are taller than the others
+    open: HashSet<Uuid>,
+}
+
+/// Whether the number typed in `query` appears in `amount`, read with
+/// either decimal separator, e.g. `12,5` in `112.50`.
+fn amount_contains(query: &str, amount: f64) -> bool {
+    let query: String = query
+        .chars()
+        .filter(|c| !c.is_whitespace())
+        .map(|c| if c == ',' { '.' } else { c })
+        .collect();
+    query.chars().any(|c| c.is_ascii_digit())
+        && query.chars().all(|c| c.is_ascii_digit() || c == '.')
+        && format!("{:.2}", amount.abs()).contains(&query)
+}
+
+impl List {
+    /// Draws the search box across the window.
+    pub fn search_box(&mut self, ui: &imgui::Ui, id: &str, hint: String) {
+        ui.set_next_item_width(-1.);
+        ui.input_text(id, &mut self.search).hint(hint).build();
+    }
+
+    /// How `name` matches the search, or `None` if neither it nor `amount`
+    /// does. Matching the amount highlights nothing.
+    pub fn find(&self, name: &str, amount: f64) -> Option<Match> {
+        fuzzy::find(&self.search, name).or_else(|| {
+            amount_contains(&self.search, amount).then(|| Match {
+                score: 0,
+                positions: Vec::new(),
+            })
+        })
+    }
+
+    /// Expands the row of `id`, e.g. to scroll to it.
+    pub fn open(&mut self, id: Uuid) {
+        self.open.insert(id);
+    }
+
+    pub fn is_open(&self, id: Uuid) -> bool {
+        self.open.contains(&id)
+    }
+
+    /// A tree node for the row of `id`, labelled `label` with the characters
+    /// of the search match highlighted. The id does not change with the
+    /// label, so rows stay expanded while their amounts change.
+    pub fn node<'ui>(
+        &mut self,
+        ui: &'ui imgui::Ui,
+        id: Uuid,
+        label: &str,
+        matched: &Match,
+    ) -> Option<TreeNodeToken<'ui>> {
+        let token = ui
+            .tree_node_config(format!("##row_{id}"))
+            .flags(TreeNodeFlags::SPAN_AVAIL_WIDTH)
+            .allow_item_overlap(true)
+            .opened(self.is_open(id), Condition::Always)
+            .push();
+        if token.is_some() {
+            self.open.insert(id);
+        } else {
+            self.open.remove(&id);
+        }
+
+        ui.same_line();
+        highlighted(ui, label, &matched.positions);
+        token
+    }
+}
+
+/// Draws `text` on the current line with the characters at `positions` in
+/// the accent colour.
+pub fn highlighted(ui: &imgui::Ui, text: &str, positions: &[usize]) {
+    let color = ui.style_color(StyleColor::NavHighlight);
+    let chars: Vec<char> = text.chars().collect();
+    let mut start = 0;
+    while start < chars.len() {
+        let matched = positions.contains(&start);
+        let end = (start..chars.len())
+            .find(|i| positions.contains(i) != matched)
+            .unwrap_or(chars.len());
+        let run: String = chars[start..end].iter().collect();
+        if start > 0 {
+            ui.same_line_with_spacing(0., 0.);
+        }
+        if matched {
+            ui.text_colored(color, run);
+        } else {
+            ui.text(run);
+        }
+        start = end;
+    }
+}
+
+/// Calls `row` for each of the rows, skipping collapsed ones scrolled out of
+/// view. Expanded rows vary in height and are always drawn, the collapsed
+/// ones between them are clipped run by run as they are all one line tall.
+pub fn rows(ui: &imgui::Ui, open: &[bool], mut row: impl FnMut(usize)) {
+    let mut start = 0;
+    while start < open.len() {
+        if open[start] {
+            row(start);
+            start += 1;
+            continue;
+        }
+        let end = open[start..]
+            .iter()
+            .position(|o| *o)
+            .map_or(open.len(), |n| start + n);
+        // Measures the first row of the run for the height of all of them
+        let clipper = ListClipper::new((end - start) as i32).begin(ui);
+        for i in clipper.iter() {
+            row(start + i as usize);
+        }
+        start = end;
+    }
+}
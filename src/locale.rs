@@ -0,0 +1,349 @@
+use chrono::NaiveDate;
+use fluent_bundle::{FluentArgs, FluentBundle, FluentResource, FluentValue};
+use serde::{Deserialize, Serialize};
+use std::env;
+use std::fmt;
+use unic_langid::LanguageIdentifier;
+
+/// Languages the interface and the reports can be shown in. Each one has a
+/// catalogue in `resources/locales`.
+#[derive(Clone, Copy, PartialEq, Eq, Debug, Serialize, Deserialize)]
+pub enum Language {
+    English,
+    Finnish,
+}
+
+impl Language {
+    pub const ALL: [Language; 2] = [Language::English, Language::Finnish];
+
+    /// What the language calls itself, so that it can be found in the list
+    /// whatever the interface is shown in.
+    pub fn name(&self) -> &'static str {
+        match self {
+            Language::English => "English",
+            Language::Finnish => "Suomi",
+        }
+    }
+
+    fn tag(&self) -> &'static str {
+        match self {
+            Language::English => "en",
+            Language::Finnish => "fi",
+        }
+    }
+
+    fn catalogue(&self) -> &'static str {
+        match self {
+            Language::English => include_str!("../resources/locales/en.ftl"),
+            Language::Finnish => include_str!("../resources/locales/fi.ftl"),
+        }
+    }
+
+    /// The language the system is set to, if there is a catalogue for it.
+    pub fn system() -> Self {
+        let locale = ["LC_ALL", "LC_MESSAGES", "LANG"]
+            .iter()
+            .filter_map(|name| env::var(name).ok())
+            .find(|value| !value.is_empty())
+            .unwrap_or_default();
+        Language::ALL
+            .into_iter()
+            .find(|l| locale.starts_with(l.tag()))
+            .unwrap_or(Language::English)
+    }
+
+    fn separators(&self) -> (char, char) {
+        match self {
+            Language::English => ('.', ','),
+            // A space that does not let the line break inside the number
+            Language::Finnish => (',', '\u{a0}'),
+        }
+    }
+
+    fn format_number(&self, value: f64, decimals: usize, grouped: bool) -> String {
+        let (point, group) = self.separators();
+        let digits = format!("{:.*}", decimals, value.abs());
+        let (whole, fraction) = digits.split_once('.').unwrap_or((&digits, ""));
+
+        let mut s = String::new();
+        // Leaves out the sign of values that round to zero
+        if value < 0. && digits.chars().any(|c| c.is_ascii_digit() && c != '0') {
+            s.push('-');
+        }
+        for (i, c) in whole.chars().enumerate() {
+            if grouped && i > 0 && (whole.len() - i) % 3 == 0 {
+                s.push(group);
+            }
+            s.push(c);
+        }
+        if !fraction.is_empty() {
+            s.push(point);
+            s += fraction;
+        }
+        s
+    }
+
+    /// Formats `value` with `decimals` decimals and grouped thousands, e.g.
+    /// `1,234.50` or `1 234,50`.
+    pub fn number(&self, value: f64, decimals: usize) -> String {
+        self.format_number(value, decimals, true)
+    }
+
+    /// Like `number` but without grouping, for files spreadsheets read.
+    pub fn plain_number(&self, value: f64, decimals: usize) -> String {
+        self.format_number(value, decimals, false)
+    }
+
+    /// Puts `symbol` where the language expects the currency.
+    pub fn money(&self, number: &str, symbol: &str) -> String {
+        match self {
+            Language::English => format!("{number}{symbol}"),
+            Language::Finnish => format!("{number}\u{a0}{symbol}"),
+        }
+    }
+
+    pub fn date(&self, date: NaiveDate) -> String {
+        match self {
+            Language::English => date.format("%Y-%m-%d").to_string(),
+            Language::Finnish => date.format("%-d.%-m.%Y").to_string(),
+        }
+    }
+
+    /// Spreadsheets set to a language with a decimal comma expect fields to
+    /// be separated by semicolons.
+    pub fn csv_delimiter(&self) -> u8 {
+        match self.separators().0 {
+            ',' => b';',
+            _ => b',',
+        }
+    }
+}
+
+/// Reads a date written the way any of the languages writes it. The formats
+/// cannot be mistaken for one another.
+pub fn parse_date(input: &str) -> Option<NaiveDate> {
+    ["%Y-%m-%d", "%d.%m.%Y"]
+        .iter()
+        .find_map(|format| NaiveDate::parse_from_str(input.trim(), format).ok())
+}
+
+/// An error or warning worked out away from the interface, as the id of its
+/// message and the values to fill in. It is translated where it is shown,
+/// with `Locale::message` or `Locale::error`.
+#[derive(Clone, Debug, PartialEq, Eq, PartialOrd, Ord)]
+pub struct Message {
+    id: &'static str,
+    args: Vec<(&'static str, Arg)>,
+}
+
+/// A value filled into a `Message`. Fluent's own values cannot be sent
+/// between threads, which errors have to be.
+#[derive(Clone, Debug, PartialEq, Eq, PartialOrd, Ord)]
+pub enum Arg {
+    Text(String),
+    /// Counts, which pick the plural of the message
+    Number(i64),
+}
+
+impl From<&str> for Arg {
+    fn from(value: &str) -> Self {
+        Arg::Text(value.to_string())
+    }
+}
+
+impl From<String> for Arg {
+    fn from(value: String) -> Self {
+        Arg::Text(value)
+    }
+}
+
+impl From<usize> for Arg {
+    fn from(value: usize) -> Self {
+        Arg::Number(value as i64)
+    }
+}
+
+impl From<u64> for Arg {
+    fn from(value: u64) -> Self {
+        Arg::Number(value as i64)
+    }
+}
+
+impl Message {
+    pub fn new(id: &'static str) -> Self {
+        Self {
+            id,
+            args: Vec::new(),
+        }
+    }
+
+    pub fn arg(mut self, name: &'static str, value: impl Into<Arg>) -> Self {
+        self.args.push((name, value.into()));
+        self
+    }
+}
+
+thread_local! {
+    static ENGLISH: Locale = Locale::default();
+}
+
+/// In English, for logs and errors that reach no window.
+impl fmt::Display for Message {
+    fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
+        f.write_str(&ENGLISH.with(|english| english.message(self)))
+    }
+}
+
+impl std::error::Error for Message {}
+
+/// The messages of the chosen language.
+pub struct Locale {
+    pub language: Language,
+    /// The chosen language first, then English for anything it lacks
+    bundles: Vec<FluentBundle<FluentResource>>,
+}
+
+impl Default for Locale {
+    fn default() -> Self {
+        Self::new(Language::English)
+    }
+}
+
+fn bundle(language: Language) -> FluentBundle<FluentResource> {
+    let id: LanguageIdentifier = language.tag().parse().expect("Invalid language tag");
+    let mut bundle = FluentBundle::new(vec![id]);
+    // The fonts have no glyphs for the marks Fluent isolates arguments with
+    bundle.set_use_isolating(false);
+
+    let resource = FluentResource::try_new(language.catalogue().to_string()).unwrap_or_else(
+        |(resource, errors)| {
+            eprintln!("Errors in the {} catalogue: {errors:?}", language.name());
+            resource
+        },
+    );
+    if let Err(errors) = bundle.add_resource(resource) {
+        eprintln!("Errors in the {} catalogue: {errors:?}", language.name());
+    }
+    bundle
+}
+
+impl Locale {
+    pub fn new(language: Language) -> Self {
+        let mut bundles = vec![bundle(language)];
+        if language != Language::English {
+            bundles.push(bundle(Language::English));
+        }
+        Self { language, bundles }
+    }
+
+    fn format(&self, id: &str, args: Option<&FluentArgs>) -> Option<String> {
+        self.bundles.iter().find_map(|bundle| {
+            let pattern = bundle.get_message(id)?.value()?;
+            let mut errors = Vec::new();
+            Some(
+                bundle
+                    .format_pattern(pattern, args, &mut errors)
+                    .into_owned(),
+            )
+        })
+    }
+
+    /// The message `id`, or the id itself if no catalogue has it.
+    pub fn tr(&self, id: &str) -> String {
+        self.format(id, None).unwrap_or_else(|| id.to_string())
+    }
+
+    /// The message `id` with its variables filled in from `args`.
+    pub fn tr_args(&self, id: &str, args: &[(&str, FluentValue)]) -> String {
+        let mut fluent_args = FluentArgs::new();
+        for (name, value) in args.iter() {
+            fluent_args.set(*name, value.clone());
+        }
+        self.format(id, Some(&fluent_args))
+            .unwrap_or_else(|| id.to_string())
+    }
+
+    /// The name of one of the offered categories in this language. Other
+    /// categories came from the user or an import and are shown as they are.
+    pub fn category(&self, category: &str) -> String {
+        self.format(&format!("category-{}", category.to_lowercase()), None)
+            .unwrap_or_else(|| category.to_string())
+    }
+
+    pub fn message(&self, message: &Message) -> String {
+        let args: Vec<(&str, FluentValue)> = message
+            .args
+            .iter()
+            .map(|(name, value)| {
+                let value = match value {
+                    Arg::Text(text) => text.as_str().into(),
+                    Arg::Number(number) => (*number).into(),
+                };
+                (*name, value)
+            })
+            .collect();
+        self.tr_args(message.id, &args)
+    }
+
+    /// `error` in this language if it is a `Message`, otherwise as it is,
+    /// e.g. for errors from the system.
+    pub fn error(&self, error: &anyhow::Error) -> String {
+        match error.downcast_ref::<Message>() {
+            Some(message) => self.message(message),
+            None => error.to_string(),
+        }
+    }
+
+    /// `categories` named in this language, for combos.
+    pub fn categories(&self, categories: &[String]) -> Vec<String> {
+        categories.iter().map(|c| self.category(c)).collect()
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn ids(language: Language) -> Vec<&'static str> {
+        let mut ids: Vec<&str> = language
+            .catalogue()
+            .lines()
+            .filter(|line| !line.starts_with([' ', '#']))
+            .filter_map(|line| line.split_once(" = ").map(|(id, _)| id))
+            .collect();
+        ids.sort();
+        ids
+    }
+
+    #[test]
+    fn catalogues_have_the_same_messages() {
+        let english = ids(Language::English);
+        for language in Language::ALL {
+            assert_eq!(ids(language), english, "{}", language.name());
+        }
+    }
+
+    #[test]
+    fn messages_are_translated_where_shown() {
+        let error = anyhow::Error::new(Message::new("invalid-date").arg("input", "32.1.2026"));
+        assert_eq!(
+            Locale::new(Language::Finnish).error(&error),
+            "'32.1.2026' ei ole kelvollinen päivämäärä"
+        );
+        assert_eq!(error.to_string(), "'32.1.2026' is not a valid date");
+        assert_eq!(
+            Locale::default().error(&anyhow::anyhow!("Disk full")),
+            "Disk full"
+        );
+
+        let skipped = |count: usize| Message::new("splitwise-payments-skipped").arg("count", count);
+        assert_eq!(
+            Locale::default().message(&skipped(1)),
+            "A settle-up payment was not imported"
+        );
+        assert_eq!(
+            Locale::default().message(&skipped(3)),
+            "3 settle-up payments were not imported"
+        );
+    }
+}
@@ -0,0 +1,114 @@
+use crate::accounting::split_cents;
+use crate::State;
+use uuid::Uuid;
+
+#[derive(Default)]
+pub struct Matrix {
+    pub open: bool,
+}
+
+fn is_shared(state: &State, participant: Uuid, receipt: Uuid) -> bool {
+    state
+        .share_map
+        .get(&participant)
+        .is_some_and(|list| list.contains(&receipt))
+}
+
+/// Participants as columns and receipts as rows, with a checkbox per cell
+/// for whether the participant shares the receipt.
+pub fn window(ui: &imgui::Ui, state: &mut State) {
+    let mut open = state.matrix.open;
+
+    ui.window(format!("{}###matrix", state.locale.tr("assignment-matrix")))
+        .size([600.0, 360.0], imgui::Condition::FirstUseEver)
+        .position([100., 60.], imgui::Condition::FirstUseEver)
+        .collapsible(false)
+        .opened(&mut open)
+        .build(|| {
+            if state.participants.is_empty() {
+                ui.text_disabled(state.locale.tr("no-participants"));
+                return;
+            }
+            if state.filter.is_active() {
+                ui.text_disabled(state.locale.tr("showing-filtered"));
+            }
+
+            let people: Vec<(Uuid, String)> = state
+                .participants
+                .iter()
+                .map(|p| (p.id, p.name.clone()))
+                .collect();
+            let rows: Vec<usize> = (0..state.receipts.len())
+                .filter(|i| state.filter.matches(state, &state.receipts[*i]))
+                .collect();
+
+            let Some(_table) = ui.begin_table_with_flags(
+                "##matrix",
+                people.len() + 1,
+                imgui::TableFlags::BORDERS
+                    | imgui::TableFlags::ROW_BG
+                    | imgui::TableFlags::SCROLL_X
+                    | imgui::TableFlags::SCROLL_Y,
+            ) else {
+                return;
+            };
+
+            // Keeps the receipt column, the names and the toggles in view
+            ui.table_setup_scroll_freeze(1, 2);
+            ui.table_setup_column(state.locale.tr("receipt"));
+            for (id, name) in people.iter() {
+                ui.table_setup_column(format!("{name}##column_{id}"));
+            }
+            ui.table_headers_row();
+
+            ui.table_next_row();
+            ui.table_next_column();
+            ui.text_disabled(state.locale.tr("everything"));
+            for (id, _) in people.iter() {
+                ui.table_next_column();
+                let mut all = !rows.is_empty()
+                    && rows
+                        .iter()
+                        .all(|r| is_shared(state, *id, state.receipts[*r].id));
+                if ui.checkbox(format!("##all_{id}"), &mut all) {
+                    for r in rows.iter() {
+                        let receipt = state.receipts[*r].id;
+                        state.set_shared(*id, receipt, all);
+                    }
+                }
+            }
+
+            let clipper = imgui::ListClipper::new(rows.len() as i32).begin(ui);
+            for row in clipper.iter() {
+                let receipt = &state.receipts[rows[row as usize]];
+                let (receipt_id, label) = (receipt.id, receipt.label.clone());
+                let cents = (receipt.total(state) * 100.).round() as i64;
+                let sharers: Vec<Uuid> = state.sharers(receipt).iter().map(|p| p.id).collect();
+                let shares = split_cents(cents, sharers.len());
+
+                ui.table_next_row();
+                ui.table_next_column();
+                let mut everyone = sharers.len() == people.len();
+                if ui.checkbox(format!("{label}##row_{receipt_id}"), &mut everyone) {
+                    for (id, _) in people.iter() {
+                        state.set_shared(*id, receipt_id, everyone);
+                    }
+                }
+
+                for (id, _) in people.iter() {
+                    ui.table_next_column();
+                    let position = sharers.iter().position(|s| s == id);
+                    let mut shared = position.is_some();
+                    if ui.checkbox(format!("##cell_{receipt_id}_{id}"), &mut shared) {
+                        state.set_shared(*id, receipt_id, shared);
+                    }
+                    if let Some(i) = position {
+                        ui.same_line();
+                        ui.text_disabled(state.settings.number(shares[i] as f64 / 100.));
+                    }
+                }
+            }
+        });
+
+    state.matrix.open = open;
+}
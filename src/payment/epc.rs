@@ -0,0 +1,115 @@
+use crate::locale::Message;
+use anyhow::Result;
+use qrcode::{Color, EcLevel, QrCode};
+use std::fs::File;
+use std::io::BufWriter;
+
+/// Modules of white border around the code, as required by the QR spec.
+const QUIET_ZONE: usize = 4;
+
+/// Builds an EPC069-12 (version 002) SEPA credit transfer payload, the
+/// contents of a "GiroCode".
+pub fn payload(name: &str, iban: &str, bic: &str, cents: i64, text: &str) -> Result<String> {
+    if name.is_empty() || name.chars().count() > 70 {
+        return Err(Message::new("epc-name-length").into());
+    }
+    if !(1..=99_999_999_999).contains(&cents) {
+        return Err(Message::new("epc-amount").into());
+    }
+    let text: String = text.chars().take(140).collect();
+
+    let payload = [
+        "BCD",
+        "002",
+        // UTF-8
+        "1",
+        "SCT",
+        bic,
+        name,
+        iban,
+        &format!("EUR{}.{:02}", cents / 100, cents % 100),
+        // Purpose code and structured reference
+        "",
+        "",
+        &text,
+    ]
+    .join("\n");
+
+    if payload.len() > 331 {
+        return Err(Message::new("epc-too-long").into());
+    }
+    Ok(payload)
+}
+
+/// The EPC guidelines mandate error correction level M.
+pub fn qr(payload: &str) -> Result<QrCode> {
+    Ok(QrCode::with_error_correction_level(payload, EcLevel::M)?)
+}
+
+/// Side length in modules, quiet zone included.
+pub fn size(code: &QrCode) -> usize {
+    code.width() + 2 * QUIET_ZONE
+}
+
+fn dark(code: &QrCode, x: usize, y: usize) -> bool {
+    let width = code.width();
+    if x < QUIET_ZONE || y < QUIET_ZONE || x >= width + QUIET_ZONE || y >= width + QUIET_ZONE {
+        return false;
+    }
+    code[(x - QUIET_ZONE, y - QUIET_ZONE)] == Color::Dark
+}
+
+/// One RGBA pixel per module, for uploading as a texture.
+pub fn rgba(code: &QrCode) -> Vec<u8> {
+    let size = size(code);
+    (0..size * size)
+        .flat_map(|i| {
+            let v = if dark(code, i % size, i / size) {
+                0
+            } else {
+                255
+            };
+            [v, v, v, 255]
+        })
+        .collect()
+}
+
+pub fn write_png(code: &QrCode, path: &str, scale: usize) -> Result<()> {
+    let size = size(code) * scale;
+    let mut encoder = png::Encoder::new(
+        BufWriter::new(File::create(path)?),
+        size as u32,
+        size as u32,
+    );
+    encoder.set_color(png::ColorType::Grayscale);
+    encoder.set_depth(png::BitDepth::Eight);
+
+    let pixels: Vec<u8> = (0..size * size)
+        .map(|i| {
+            if dark(code, (i % size) / scale, (i / size) / scale) {
+                0
+            } else {
+                255
+            }
+        })
+        .collect();
+    encoder.write_header()?.write_image_data(&pixels)?;
+    Ok(())
+}
+
+pub fn svg(code: &QrCode) -> String {
+    let size = size(code);
+    let mut s = format!(
+        "<svg xmlns=\"http://www.w3.org/2000/svg\" viewBox=\"0 0 {size} {size}\" shape-rendering=\"crispEdges\">\n\
+         <rect width=\"{size}\" height=\"{size}\" fill=\"#fff\"/>\n<path fill=\"#000\" d=\""
+    );
+    for y in 0..size {
+        for x in 0..size {
+            if dark(code, x, y) {
+                s += &format!("M{x} {y}h1v1h-1z");
+            }
+        }
+    }
+    s += "\"/>\n</svg>\n";
+    s
+}
@@ -0,0 +1,154 @@
+use crate::locale::Message;
+use anyhow::Result;
+
+/// IBAN lengths of the SEPA countries we are likely to see. Others are only
+/// checked against the general 15–34 character limit.
+const LENGTHS: [(&str, usize); 20] = [
+    ("AT", 20),
+    ("AX", 18),
+    ("BE", 16),
+    ("CH", 21),
+    ("DE", 22),
+    ("DK", 18),
+    ("EE", 20),
+    ("ES", 24),
+    ("FI", 18),
+    ("FR", 27),
+    ("GB", 22),
+    ("IE", 22),
+    ("IT", 27),
+    ("LT", 20),
+    ("LV", 21),
+    ("NL", 18),
+    ("NO", 15),
+    ("PL", 28),
+    ("PT", 25),
+    ("SE", 24),
+];
+
+/// Strips spaces and upper-cases, e.g. `fi21 1234 5600 0007 85` into
+/// `FI2112345600000785`.
+pub fn normalize(iban: &str) -> String {
+    iban.chars()
+        .filter(|c| !c.is_whitespace())
+        .collect::<String>()
+        .to_uppercase()
+}
+
+/// Groups a normalized IBAN into blocks of four for display.
+pub fn format(iban: &str) -> String {
+    normalize(iban)
+        .chars()
+        .collect::<Vec<_>>()
+        .chunks(4)
+        .map(|c| c.iter().collect::<String>())
+        .collect::<Vec<_>>()
+        .join(" ")
+}
+
+/// Remainder of a string of digits and letters (A = 10 … Z = 35) modulo 97,
+/// as used by both IBAN and RF creditor reference check digits.
+pub fn mod97(input: &str) -> Option<u32> {
+    input.chars().try_fold(0u32, |acc, c| {
+        let value = c.to_digit(36)?;
+        Some(if value < 10 {
+            (acc * 10 + value) % 97
+        } else {
+            (acc * 100 + value) % 97
+        })
+    })
+}
+
+pub fn validate_iban(iban: &str) -> Result<()> {
+    let iban = normalize(iban);
+    if !(15..=34).contains(&iban.len()) {
+        return Err(Message::new("iban-length").into());
+    }
+    if !iban.chars().all(|c| c.is_ascii_alphanumeric())
+        || !iban[..2].chars().all(|c| c.is_ascii_alphabetic())
+        || !iban[2..4].chars().all(|c| c.is_ascii_digit())
+    {
+        return Err(Message::new("iban-shape").into());
+    }
+    if let Some((country, length)) = LENGTHS.iter().find(|(c, _)| *c == &iban[..2]) {
+        if iban.len() != *length {
+            let message = Message::new("iban-country-length")
+                .arg("country", *country)
+                .arg("length", *length);
+            return Err(message.into());
+        }
+    }
+
+    let rearranged = format!("{}{}", &iban[4..], &iban[..4]);
+    match mod97(&rearranged) {
+        Some(1) => Ok(()),
+        _ => Err(Message::new("iban-check-digits").into()),
+    }
+}
+
+/// Checks the shape of a BIC: bank code, country, location and an optional
+/// branch, e.g. `NDEAFIHH` or `OKOYFIHHXXX`.
+pub fn validate_bic(bic: &str) -> Result<()> {
+    let bic = normalize(bic);
+    // Only ASCII can be sliced by bytes
+    let shape_ok = (bic.len() == 8 || bic.len() == 11)
+        && bic.chars().all(|c| c.is_ascii_alphanumeric())
+        && bic[..6].chars().all(|c| c.is_ascii_alphabetic())
+        && bic[6..].chars().all(|c| c.is_ascii_alphanumeric());
+    if shape_ok {
+        Ok(())
+    } else {
+        Err(Message::new("bic-shape").into())
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn ibans() {
+        for iban in [
+            "FI21 1234 5600 0007 85",
+            "fi2112345600000785",
+            "DE89 3704 0044 0532 0130 00",
+            "GB29 NWBK 6016 1331 9268 19",
+            "NO93 8601 1117 947",
+        ] {
+            assert!(validate_iban(iban).is_ok(), "{iban}");
+        }
+        for iban in [
+            "",
+            "FI21 1234 5600 0007 86",
+            "FI21 1234 5600 0007 8",
+            "1I21 1234 5600 0007 85",
+            "FIAB 1234 5600 0007 85",
+            "FI21 1234 5600 0007 8-",
+            "ÄÄ21 1234 5600 0007 85",
+            "FI2Ä 1234 5600 0007 85",
+            "FI21 1234 5600 0007 ÄÖ",
+        ] {
+            assert!(validate_iban(iban).is_err(), "{iban}");
+        }
+    }
+
+    #[test]
+    fn bics() {
+        for bic in ["NDEAFIHH", "okoyfihh", "OKOYFIHHXXX", "DEUTDEFF500"] {
+            assert!(validate_bic(bic).is_ok(), "{bic}");
+        }
+        for bic in [
+            "",
+            "NDEAFIH",
+            "NDEAFIHHX",
+            "NDE4FIHH",
+            "NDEAFIH-",
+            "ÄÄÄÄÄÄ1",
+            "ÄÄÄÄ",
+            "NDEAFIÄ",
+            "NDEAFIHHXÄ",
+        ] {
+            assert!(validate_bic(bic).is_err(), "{bic}");
+        }
+    }
+}
@@ -0,0 +1,158 @@
+use crate::locale::parse_date;
+use crate::State;
+use chrono::NaiveDate;
+use serde::{Deserialize, Serialize};
+use uuid::Uuid;
+
+/// Money actually handed over between two participants, settling part or
+/// all of what one owes the other.
+#[derive(Clone, Debug, Serialize, Deserialize)]
+pub struct Payment {
+    pub id: Uuid,
+    pub from: Uuid,
+    pub to: Uuid,
+    pub amount: f32,
+    pub date: NaiveDate,
+    pub note: String,
+}
+
+impl Payment {
+    pub fn new(from: Uuid, to: Uuid, amount: f32, date: NaiveDate, note: String) -> Self {
+        Self {
+            id: Uuid::new_v4(),
+            from,
+            to,
+            amount,
+            date,
+            note,
+        }
+    }
+}
+
+pub struct PaymentForm {
+    from: usize,
+    to: usize,
+    amount: f32,
+    date: String,
+    note: String,
+}
+
+impl Default for PaymentForm {
+    fn default() -> Self {
+        Self {
+            from: 0,
+            to: 0,
+            amount: 0.,
+            date: chrono::Local::now().date_naive().to_string(),
+            note: String::new(),
+        }
+    }
+}
+
+/// The "Record payment" form and the list of recorded payments.
+pub fn ui(ui: &imgui::Ui, state: &mut State) {
+    let locale = &state.locale;
+    let mut changed = false;
+    ui.tree_node_config(format!("{}##record_payment", locale.tr("record-payment")))
+        .build(|| {
+            if state.participants.len() < 2 {
+                ui.text_disabled(locale.tr("payments-need-two"));
+                return;
+            }
+
+            let names: Vec<String> = state.participants.iter().map(|p| p.name.clone()).collect();
+            let form = &mut state.payment_form;
+            form.from = form.from.min(names.len() - 1);
+            form.to = form.to.min(names.len() - 1);
+
+            ui.combo_simple_string(
+                format!("{}##payment_from", locale.tr("payment-from")),
+                &mut form.from,
+                &names,
+            );
+            ui.combo_simple_string(
+                format!("{}##payment_to", locale.tr("payment-to")),
+                &mut form.to,
+                &names,
+            );
+            ui.input_float(
+                format!("{}##payment_amount", locale.tr("amount")),
+                &mut form.amount,
+            )
+            .build();
+            ui.input_text(
+                format!("{}##payment_date", locale.tr("date")),
+                &mut form.date,
+            )
+            .hint(locale.tr("date-hint"))
+            .build();
+            ui.input_text(
+                format!("{}##payment_note", locale.tr("note")),
+                &mut form.note,
+            )
+            .hint(locale.tr_args("example", &[("value", "MobilePay".into())]))
+            .build();
+
+            let date = parse_date(&form.date);
+            let _danger_token =
+                ui.begin_disabled(form.from == form.to || form.amount <= 0. || date.is_none());
+            if ui.button(locale.tr("record")) {
+                let payment = Payment::new(
+                    state.participants[form.from].id,
+                    state.participants[form.to].id,
+                    form.amount,
+                    date.unwrap(),
+                    form.note.clone(),
+                );
+                form.amount = 0.;
+                form.note.clear();
+                state.payments.push(payment);
+                changed = true;
+            }
+        });
+
+    ui.tree_node_config(format!(
+        "{}###recorded_payments",
+        locale.tr_args(
+            "recorded-payments",
+            &[("count", state.payments.len().into())]
+        )
+    ))
+    .build(|| {
+        let name = |id| {
+            state
+                .participants
+                .iter()
+                .find(|p| p.id == id)
+                .map_or(locale.tr("removed"), |p| p.name.clone())
+        };
+
+        let mut remove = None;
+        for payment in state.payments.iter() {
+            ui.text(format!(
+                "{} {} -> {} {}",
+                state.settings.date(payment.date),
+                name(payment.from),
+                name(payment.to),
+                state.settings.number(f64::from(payment.amount))
+            ));
+            if !payment.note.is_empty() {
+                ui.same_line();
+                ui.text_disabled(&payment.note);
+            }
+            ui.same_line();
+            if ui.small_button(format!("{}##payment_{}", locale.tr("remove"), payment.id)) {
+                remove = Some(payment.id);
+            }
+        }
+
+        if let Some(id) = remove {
+            state.payments.retain(|p| p.id != id);
+            changed = true;
+        }
+    });
+
+    if changed {
+        state.session_changed();
+    }
+}
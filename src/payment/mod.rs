@@ -0,0 +1,298 @@
+use crate::accounting::split_cents;
+use crate::support::Textures;
+use crate::State;
+use std::collections::HashMap;
+use std::fs;
+use uuid::Uuid;
+
+pub mod epc;
+pub mod iban;
+pub mod ledger;
+pub mod reference;
+pub mod statement;
+
+/// Money one participant should send another to settle up.
+#[derive(Clone, Copy, PartialEq, Eq, Debug)]
+pub struct Transfer {
+    pub from: Uuid,
+    pub to: Uuid,
+    pub cents: i64,
+}
+
+/// What every participant has paid minus what they owe, in cents, after the
+/// recorded payments. Receipts without a payer or without anyone sharing
+/// them are left out.
+pub fn balances(state: &State) -> HashMap<Uuid, i64> {
+    let mut balances: HashMap<Uuid, i64> = state.participants.iter().map(|p| (p.id, 0)).collect();
+    let shares = state.shares();
+
+    for receipt in state.receipts.iter() {
+        let sharers = shares.sharers(receipt.id);
+        let Some(payer) = state.payer_of(receipt) else {
+            continue;
+        };
+        if sharers.is_empty() || !balances.contains_key(&payer) {
+            continue;
+        }
+
+        let cents = (shares.total_of(receipt) * 100.).round() as i64;
+        *balances.get_mut(&payer).unwrap() += cents;
+        for (p, share) in sharers.iter().zip(split_cents(cents, sharers.len())) {
+            *balances.get_mut(p).unwrap() -= share;
+        }
+    }
+
+    for payment in state.payments.iter() {
+        let cents = (payment.amount * 100.).round() as i64;
+        if let Some(b) = balances.get_mut(&payment.from) {
+            *b += cents;
+        }
+        if let Some(b) = balances.get_mut(&payment.to) {
+            *b -= cents;
+        }
+    }
+
+    balances
+}
+
+/// Settles `balances` by repeatedly having the largest debtor pay the
+/// largest creditor, which needs at most one transfer fewer than there are
+/// people.
+pub fn settle(balances: &HashMap<Uuid, i64>, order: &[Uuid]) -> Vec<Transfer> {
+    let mut open: Vec<(Uuid, i64)> = order
+        .iter()
+        .filter_map(|id| balances.get(id).map(|b| (*id, *b)))
+        .filter(|(_, b)| *b != 0)
+        .collect();
+    let mut transfers = Vec::new();
+
+    loop {
+        let debtor = open.iter().enumerate().min_by_key(|(_, (_, b))| *b);
+        let creditor = open.iter().enumerate().max_by_key(|(_, (_, b))| *b);
+        let (Some((d, &(from, owes))), Some((c, &(to, owed)))) = (debtor, creditor) else {
+            break;
+        };
+        if owes >= 0 || owed <= 0 {
+            break;
+        }
+
+        let cents = i64::min(-owes, owed);
+        transfers.push(Transfer { from, to, cents });
+        open[d].1 += cents;
+        open[c].1 -= cents;
+    }
+
+    transfers
+}
+
+pub fn transfers(state: &State) -> Vec<Transfer> {
+    let order: Vec<Uuid> = state.participants.iter().map(|p| p.id).collect();
+    settle(&state.balances(), &order)
+}
+
+fn name(state: &State, id: Uuid) -> String {
+    state
+        .participants
+        .iter()
+        .find(|p| p.id == id)
+        .map(|p| p.name.clone())
+        .unwrap_or_default()
+}
+
+struct Code {
+    transfer: Transfer,
+    code: qrcode::QrCode,
+    texture: imgui::TextureId,
+}
+
+#[derive(Default)]
+pub struct Settlement {
+    pub open: bool,
+    code: Option<Code>,
+    export_path: String,
+    message: Option<String>,
+}
+
+impl Settlement {
+    fn clear_code(&mut self, textures: &mut Textures) {
+        if let Some(code) = self.code.take() {
+            textures.remove(code.texture);
+        }
+    }
+}
+
+fn show_code(state: &mut State, textures: &mut Textures, transfer: Transfer) {
+    let payee = state.participants.iter().find(|p| p.id == transfer.to);
+    let payer = state.participants.iter().find(|p| p.id == transfer.from);
+    let (Some(payee), Some(payer)) = (payee, payer) else {
+        return;
+    };
+
+    let code = epc::payload(
+        &payee.name,
+        &iban::normalize(&payee.iban),
+        &iban::normalize(&payee.bic),
+        transfer.cents,
+        &state
+            .locale
+            .tr_args("epc-remittance", &[("name", payer.name.as_str().into())]),
+    )
+    .and_then(|payload| epc::qr(&payload));
+
+    let locale = &state.locale;
+    let settlement = &mut state.settlement;
+    settlement.clear_code(textures);
+    settlement.message = None;
+
+    let result = code.and_then(|code| {
+        let size = epc::size(&code) as u32;
+        let texture = textures.upload_rgba(size, size, epc::rgba(&code))?;
+        Ok(Code {
+            transfer,
+            code,
+            texture,
+        })
+    });
+    match result {
+        Ok(code) => settlement.code = Some(code),
+        Err(e) => settlement.message = Some(locale.error(&e)),
+    }
+}
+
+pub fn window(ui: &imgui::Ui, state: &mut State, textures: &mut Textures) {
+    let mut open = state.settlement.open;
+
+    ui.window(format!("{}###settlement", state.locale.tr("settlement")))
+        .size([420.0, 440.0], imgui::Condition::FirstUseEver)
+        .position([190., 30.], imgui::Condition::FirstUseEver)
+        .collapsible(false)
+        .opened(&mut open)
+        .build(|| {
+            let transfers = transfers(state);
+            if transfers.is_empty() {
+                ui.text_disabled(state.locale.tr("settled-up"));
+            }
+
+            let mut selected = None;
+            let mut record = None;
+            for (i, transfer) in transfers.iter().enumerate() {
+                ui.text(state.locale.tr_args(
+                    "transfer",
+                    &[
+                        ("from", name(state, transfer.from).into()),
+                        ("to", name(state, transfer.to).into()),
+                        (
+                            "amount",
+                            state.settings.number(transfer.cents as f64 / 100.).into(),
+                        ),
+                    ],
+                ));
+                ui.same_line();
+
+                let payee = state.participants.iter().find(|p| p.id == transfer.to);
+                let has_iban = payee.is_some_and(|p| iban::validate_iban(&p.iban).is_ok());
+                let _danger_token = ui.begin_disabled(!has_iban);
+                if ui.small_button(format!("QR##transfer_{i}")) {
+                    selected = Some(*transfer);
+                }
+                if !has_iban
+                    && ui.is_item_hovered_with_flags(imgui::ItemHoveredFlags::ALLOW_WHEN_DISABLED)
+                {
+                    ui.tooltip_text(state.locale.tr_args(
+                        "no-valid-iban",
+                        &[("name", name(state, transfer.to).into())],
+                    ));
+                }
+                drop(_danger_token);
+                ui.same_line();
+                if ui.small_button(format!("{}##paid_{i}", state.locale.tr("paid"))) {
+                    record = Some(*transfer);
+                }
+            }
+
+            if let Some(transfer) = selected {
+                show_code(state, textures, transfer);
+            }
+
+            // Settles the suggested transfer in full, partial payments go
+            // through the form below
+            if let Some(transfer) = record {
+                state.payments.push(ledger::Payment::new(
+                    transfer.from,
+                    transfer.to,
+                    transfer.cents as f32 / 100.,
+                    chrono::Local::now().date_naive(),
+                    String::new(),
+                ));
+                state.session_changed();
+            }
+
+            ui.separator();
+            ledger::ui(ui, state);
+
+            // The balances moved since the code was made
+            if state
+                .settlement
+                .code
+                .as_ref()
+                .is_some_and(|c| !transfers.contains(&c.transfer))
+            {
+                state.settlement.clear_code(textures);
+            }
+
+            let (payee, payee_iban) = state
+                .settlement
+                .code
+                .as_ref()
+                .and_then(|c| state.participants.iter().find(|p| p.id == c.transfer.to))
+                .map(|p| (p.name.clone(), iban::format(&p.iban)))
+                .unwrap_or_default();
+            let locale = &state.locale;
+            let amount = |cents: i64| state.settings.number(cents as f64 / 100.);
+            let settlement = &mut state.settlement;
+            if let Some(message) = &settlement.message {
+                ui.text_wrapped(message);
+            }
+
+            let Some(code) = &settlement.code else {
+                return;
+            };
+
+            ui.separator();
+            ui.text(locale.tr_args(
+                "scan-to-pay",
+                &[
+                    ("amount", amount(code.transfer.cents).into()),
+                    ("name", payee.into()),
+                ],
+            ));
+            ui.text_disabled(payee_iban);
+            imgui::Image::new(code.texture, [200., 200.]).build(ui);
+
+            ui.input_text("##qr_export", &mut settlement.export_path)
+                .hint(locale.tr("qr-export-hint"))
+                .build();
+            let _danger_token = ui.begin_disabled(settlement.export_path.is_empty());
+            ui.same_line();
+            if ui.button(locale.tr("save-png")) {
+                let path = format!("{}.png", settlement.export_path.trim_end_matches(".png"));
+                settlement.message = Some(match epc::write_png(&code.code, &path, 8) {
+                    Ok(()) => locale.tr_args("wrote-file", &[("path", path.into())]),
+                    Err(e) => locale.error(&e),
+                });
+            }
+            ui.same_line();
+            if ui.button(locale.tr("save-svg")) {
+                let path = format!("{}.svg", settlement.export_path.trim_end_matches(".svg"));
+                settlement.message = Some(match fs::write(&path, epc::svg(&code.code)) {
+                    Ok(()) => locale.tr_args("wrote-file", &[("path", path.into())]),
+                    Err(e) => e.to_string(),
+                });
+            }
+        });
+
+    if !open {
+        state.settlement.clear_code(textures);
+    }
+    state.settlement.open = open;
+}
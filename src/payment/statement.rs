@@ -0,0 +1,317 @@
+use super::{iban, reference, transfers, Transfer};
+use crate::accounting::split_cents;
+use crate::locale::{parse_date, Locale, Message};
+use crate::{Participant, State};
+use anyhow::Result;
+use chrono::NaiveDate;
+
+/// Payment details for one transfer the statement's participant has to make.
+pub struct PaymentRequest {
+    pub transfer: Transfer,
+    pub payee: String,
+    pub iban: String,
+    pub barcode: Result<String>,
+}
+
+/// Everything a single participant needs to know to settle up.
+pub struct Statement {
+    /// Identifies the participant and period on their payments
+    pub reference: Result<String>,
+    pub shares: Vec<(String, i64)>,
+    pub paid: Vec<(String, i64)>,
+    pub to_pay: Vec<PaymentRequest>,
+    pub to_receive: Vec<(String, i64)>,
+}
+
+fn money(locale: &Locale, cents: i64) -> String {
+    locale.language.number(cents as f64 / 100., 2)
+}
+
+impl Statement {
+    pub fn new(
+        state: &State,
+        participant: &Participant,
+        period: &str,
+        due: Option<NaiveDate>,
+        creditor_reference: bool,
+    ) -> Self {
+        let mut shares = Vec::new();
+        let mut paid = Vec::new();
+        for receipt in state.receipts.iter() {
+            let sharers = state.sharers(receipt);
+            let cents = (receipt.total(state) * 100.).round() as i64;
+            if let Some(i) = sharers.iter().position(|p| p.id == participant.id) {
+                shares.push((receipt.label.clone(), split_cents(cents, sharers.len())[i]));
+            }
+            if !sharers.is_empty() && state.payer_of(receipt) == Some(participant.id) {
+                paid.push((receipt.label.clone(), cents));
+            }
+        }
+
+        let name = |id| {
+            state
+                .participants
+                .iter()
+                .find(|p| p.id == id)
+                .map(|p| (p.name.clone(), p.iban.clone()))
+                .unwrap_or_default()
+        };
+
+        let reference = reference::for_participant(&participant.id, period).and_then(|r| {
+            if creditor_reference {
+                reference::creditor(&r)
+            } else {
+                Ok(r)
+            }
+        });
+
+        let mut to_pay = Vec::new();
+        let mut to_receive = Vec::new();
+        for transfer in transfers(state) {
+            if transfer.from == participant.id {
+                let (payee, iban) = name(transfer.to);
+                let barcode = match &reference {
+                    Ok(r) => reference::virtual_barcode(&iban, transfer.cents, r, due),
+                    Err(_) => Err(Message::new("no-reference").into()),
+                };
+                to_pay.push(PaymentRequest {
+                    transfer,
+                    payee,
+                    iban,
+                    barcode,
+                });
+            } else if transfer.to == participant.id {
+                to_receive.push((name(transfer.from).0, transfer.cents));
+            }
+        }
+
+        Self {
+            reference,
+            shares,
+            paid,
+            to_pay,
+            to_receive,
+        }
+    }
+
+    pub fn owed(&self) -> i64 {
+        self.shares.iter().map(|(_, c)| c).sum()
+    }
+
+    pub fn paid(&self) -> i64 {
+        self.paid.iter().map(|(_, c)| c).sum()
+    }
+
+    /// The statement as plain text in the language of `locale`.
+    pub fn to_text(&self, locale: &Locale, name: &str) -> String {
+        let total = locale.tr("total");
+        let mut s = format!(
+            "{}\n\n",
+            locale.tr_args("statement-for", &[("name", name.into())])
+        );
+
+        s += &format!("{}\n", locale.tr("share-of-receipts"));
+        for (label, cents) in self.shares.iter() {
+            s += &format!("  {label}: {}\n", money(locale, *cents));
+        }
+        s += &format!("  {total}: {}\n\n", money(locale, self.owed()));
+
+        if !self.paid.is_empty() {
+            s += &format!("{}\n", locale.tr("paid-for-group"));
+            for (label, cents) in self.paid.iter() {
+                s += &format!("  {label}: {}\n", money(locale, *cents));
+            }
+            s += &format!("  {total}: {}\n\n", money(locale, self.paid()));
+        }
+
+        for request in self.to_pay.iter() {
+            s += &format!(
+                "{}\n  IBAN: {}\n",
+                locale.tr_args(
+                    "pay-to",
+                    &[
+                        ("amount", money(locale, request.transfer.cents).into()),
+                        ("name", request.payee.as_str().into()),
+                    ],
+                ),
+                iban::format(&request.iban)
+            );
+            if let Ok(r) = &self.reference {
+                s += &format!("  {}: {}\n", locale.tr("reference"), reference::format(r));
+            }
+            if let Ok(b) = &request.barcode {
+                s += &format!("  {}: {b}\n", locale.tr("virtual-barcode"));
+            }
+            s += "\n";
+        }
+
+        for (from, cents) in self.to_receive.iter() {
+            s += &format!(
+                "{}\n",
+                locale.tr_args(
+                    "pays-you",
+                    &[
+                        ("from", from.as_str().into()),
+                        ("amount", money(locale, *cents).into()),
+                    ],
+                )
+            );
+        }
+
+        s
+    }
+}
+
+pub struct Statements {
+    pub open: bool,
+    participant: usize,
+    period: String,
+    due: String,
+    creditor_reference: bool,
+}
+
+impl Default for Statements {
+    fn default() -> Self {
+        Self {
+            open: false,
+            participant: 0,
+            period: chrono::Local::now().format("%Y%m").to_string(),
+            due: String::new(),
+            creditor_reference: false,
+        }
+    }
+}
+
+fn copyable(ui: &imgui::Ui, locale: &Locale, label: &str, value: &str, id: usize) {
+    ui.text(format!("{label}: {value}"));
+    ui.same_line();
+    if ui.small_button(format!("{}##{label}_{id}", locale.tr("copy"))) {
+        ui.set_clipboard_text(value);
+    }
+}
+
+pub fn window(ui: &imgui::Ui, state: &mut State) {
+    let mut open = state.statements.open;
+
+    ui.window(format!("{}###statements", state.locale.tr("statements")))
+        .size([480.0, 440.0], imgui::Condition::FirstUseEver)
+        .position([160., 30.], imgui::Condition::FirstUseEver)
+        .collapsible(false)
+        .opened(&mut open)
+        .build(|| {
+            let locale = &state.locale;
+            let palette = state.settings.palette();
+            if state.participants.is_empty() {
+                ui.text_disabled(locale.tr("no-participants"));
+                return;
+            }
+
+            let names: Vec<String> = state.participants.iter().map(|p| p.name.clone()).collect();
+            let statements = &mut state.statements;
+            statements.participant = statements.participant.min(names.len() - 1);
+            ui.combo_simple_string(
+                format!("{}##statement_participant", locale.tr("participant")),
+                &mut statements.participant,
+                &names,
+            );
+            ui.input_text(
+                format!("{}##statement_period", locale.tr("period")),
+                &mut statements.period,
+            )
+            .chars_decimal(true)
+            .build();
+            ui.input_text(
+                format!("{}##statement_due", locale.tr("due-date")),
+                &mut statements.due,
+            )
+            .hint(locale.tr("date-hint-optional"))
+            .build();
+            ui.checkbox(
+                format!("{}##creditor_reference", locale.tr("rf-references")),
+                &mut statements.creditor_reference,
+            );
+
+            let due = parse_date(&statements.due);
+            if !statements.due.trim().is_empty() && due.is_none() {
+                ui.text_colored(
+                    state.settings.palette().error,
+                    locale.tr("invalid-due-date"),
+                );
+            }
+
+            let participant = &state.participants[state.statements.participant];
+            let statement = Statement::new(
+                state,
+                participant,
+                &state.statements.period,
+                due,
+                state.statements.creditor_reference,
+            );
+
+            ui.separator();
+            ui.text(locale.tr_args(
+                "share-and-paid",
+                &[
+                    ("share", money(locale, statement.owed()).into()),
+                    ("paid", money(locale, statement.paid()).into()),
+                ],
+            ));
+
+            for (i, request) in statement.to_pay.iter().enumerate() {
+                ui.separator();
+                ui.text_colored(
+                    palette.debt,
+                    locale.tr_args(
+                        "pay-to",
+                        &[
+                            ("amount", money(locale, request.transfer.cents).into()),
+                            ("name", request.payee.as_str().into()),
+                        ],
+                    ),
+                );
+                if iban::validate_iban(&request.iban).is_ok() {
+                    copyable(ui, locale, "IBAN", &iban::format(&request.iban), i);
+                } else {
+                    ui.text_disabled(
+                        locale.tr_args("no-valid-iban", &[("name", request.payee.as_str().into())]),
+                    );
+                }
+                match &statement.reference {
+                    Ok(r) => copyable(
+                        ui,
+                        locale,
+                        &locale.tr("reference"),
+                        &reference::format(r),
+                        i,
+                    ),
+                    Err(e) => ui.text_colored(state.settings.palette().error, locale.error(e)),
+                }
+                match &request.barcode {
+                    Ok(b) => copyable(ui, locale, &locale.tr("virtual-barcode"), b, i),
+                    Err(e) => ui.text_disabled(
+                        locale.tr_args("no-virtual-barcode", &[("error", locale.error(e).into())]),
+                    ),
+                }
+            }
+
+            for (from, cents) in statement.to_receive.iter() {
+                ui.text_colored(
+                    palette.credit,
+                    locale.tr_args(
+                        "pays",
+                        &[
+                            ("from", from.as_str().into()),
+                            ("amount", money(locale, *cents).into()),
+                        ],
+                    ),
+                );
+            }
+
+            ui.separator();
+            if ui.button(locale.tr("copy-statement")) {
+                ui.set_clipboard_text(statement.to_text(locale, &participant.name));
+            }
+        });
+
+    state.statements.open = open;
+}
@@ -0,0 +1,306 @@
+use crate::locale::{Language, Locale};
+use crate::settings::Settings;
+use crate::{Participant, Receipt, State};
+use proptest::prelude::*;
+use uuid::Uuid;
+
+/// Names repeat on purpose, as nothing should tell participants apart by name
+const NAMES: [&str; 4] = ["Alice", "Bob", "Alice", "Chen"];
+
+/// A session to build in any order.
+#[derive(Clone, Debug)]
+struct Spec {
+    names: Vec<&'static str>,
+    /// Each receipt's total and the totals of the items excluded from it,
+    /// in cents. Items may add up to more than the receipt.
+    receipts: Vec<(i64, Vec<i64>)>,
+    /// Who pays each receipt, by index into `names`
+    payers: Vec<Option<usize>>,
+    /// Whether each participant shares each row, counting the receipts and
+    /// their items in order
+    shared: Vec<Vec<bool>>,
+}
+
+impl Spec {
+    /// Where the rows of receipt `r` start in `shared`.
+    fn row(&self, r: usize) -> usize {
+        self.receipts[..r]
+            .iter()
+            .map(|(_, items)| 1 + items.len())
+            .sum()
+    }
+}
+
+fn participant_id(p: usize) -> Uuid {
+    Uuid::from_u128(p as u128 + 1)
+}
+
+fn receipt_id(row: usize) -> Uuid {
+    Uuid::from_u128(row as u128 + 1_000)
+}
+
+fn cents(amount: i64) -> f32 {
+    amount as f32 / 100.
+}
+
+fn spec() -> impl Strategy<Value = Spec> {
+    (
+        prop::collection::vec(prop::sample::select(&NAMES[..]), 1..6),
+        prop::collection::vec(
+            (0..100_000i64, prop::collection::vec(0..50_000i64, 0..4)),
+            0..10,
+        ),
+    )
+        .prop_flat_map(|(names, receipts)| {
+            let rows: usize = receipts.iter().map(|(_, items)| 1 + items.len()).sum();
+            let people = names.len();
+            (
+                Just(names),
+                Just(receipts.clone()),
+                prop::collection::vec(prop::option::of(0..people), receipts.len()),
+                prop::collection::vec(prop::collection::vec(any::<bool>(), rows), people),
+            )
+        })
+        .prop_map(|(names, receipts, payers, shared)| Spec {
+            names,
+            receipts,
+            payers,
+            shared,
+        })
+}
+
+/// A session with the orders the participants and receipts are added in.
+fn reordered() -> impl Strategy<Value = (Spec, Vec<usize>, Vec<usize>)> {
+    spec().prop_flat_map(|spec| {
+        let people: Vec<usize> = (0..spec.names.len()).collect();
+        let receipts: Vec<usize> = (0..spec.receipts.len()).collect();
+        (
+            Just(spec),
+            Just(people).prop_shuffle(),
+            Just(receipts).prop_shuffle(),
+        )
+    })
+}
+
+/// Builds `spec` through the same calls the interface makes, adding the
+/// participants and receipts in the given orders.
+fn build(spec: &Spec, people: &[usize], receipts: &[usize]) -> State {
+    let settings = Settings {
+        language: Language::English,
+        currency_symbol: String::new(),
+        ..Settings::default()
+    };
+    let mut state = State {
+        locale: Locale::new(settings.language),
+        settings,
+        ..Default::default()
+    };
+
+    for &p in people {
+        let mut participant = Participant::new(spec.names[p].to_string());
+        participant.id = participant_id(p);
+        state.add_participant(participant);
+    }
+    for &r in receipts {
+        let (total, items) = &spec.receipts[r];
+        let row = spec.row(r);
+        let mut receipt = Receipt::new(format!("R{r}"), cents(*total), false);
+        receipt.id = receipt_id(row);
+        receipt.payer = spec.payers[r].map(participant_id);
+        state.add_receipt(receipt);
+        for (i, total) in items.iter().enumerate() {
+            let mut item = Receipt::new(format!("R{r}.{i}"), cents(*total), true);
+            item.id = receipt_id(row + 1 + i);
+            state.exclude(receipt_id(row), item);
+        }
+    }
+    for &p in people {
+        for &r in receipts {
+            let row = spec.row(r);
+            for n in row..row + 1 + spec.receipts[r].1.len() {
+                if spec.shared[p][n] {
+                    state.set_shared(participant_id(p), receipt_id(n), true);
+                }
+            }
+        }
+    }
+    state
+}
+
+fn in_order(spec: &Spec) -> State {
+    let people: Vec<usize> = (0..spec.names.len()).collect();
+    let receipts: Vec<usize> = (0..spec.receipts.len()).collect();
+    build(spec, &people, &receipts)
+}
+
+/// Equal but for the rounding of adding up `f32`s in another order.
+fn close(a: f64, b: f64) -> bool {
+    (a - b).abs() <= 0.01 + a.abs().max(b.abs()) * 1e-5
+}
+
+/// The CSV export as participant lines and receipt lines, each receipt line
+/// with the participant it is listed under, sorted to compare regardless of
+/// order, and the total.
+type Csv = (Vec<(String, f64)>, Vec<(String, String, String)>, f64);
+
+fn read_csv(export: &str) -> Csv {
+    let mut reader = csv::Reader::from_reader(export.as_bytes());
+    let records: Vec<csv::StringRecord> = reader.records().map(|r| r.unwrap()).collect();
+    let (total, records) = records.split_last().expect("The total is always there");
+
+    let mut people = Vec::new();
+    let mut receipts = Vec::new();
+    for record in records {
+        if record[0].is_empty() {
+            let name = people.last().map(|(name, _): &(String, f64)| name.clone());
+            receipts.push((
+                name.expect("Receipts are listed under a participant"),
+                record[2].to_string(),
+                record[1].to_string(),
+            ));
+        } else {
+            people.push((record[0].to_string(), record[1].parse().unwrap()));
+        }
+    }
+    people.sort_by(|a, b| a.0.cmp(&b.0).then(a.1.total_cmp(&b.1)));
+    receipts.sort();
+    (people, receipts, total[1].parse().unwrap())
+}
+
+/// The receipt lines of the text export, with the participant each is
+/// listed under, sorted and without the padding.
+fn read_txt(export: &str) -> Vec<(String, String)> {
+    let lines: Vec<Vec<&str>> = export
+        .lines()
+        .map(|l| l.split_whitespace().collect())
+        .collect();
+    // Past the title, the headings and the rule under them
+    let mut name = String::new();
+    let mut receipts = Vec::new();
+    for tokens in lines.iter().skip(4) {
+        match tokens.as_slice() {
+            [">", rest @ ..] => receipts.push((name.clone(), rest.join(" "))),
+            [participant, _] => name = participant.to_string(),
+            _ => {}
+        }
+    }
+    receipts.sort();
+    receipts
+}
+
+proptest! {
+    /// Everyone's shares add up to the receipts that someone shares, after
+    /// the excluded items are taken out and shared on their own.
+    #[test]
+    fn shares_add_up_to_shared_receipts(spec in spec()) {
+        let state = in_order(&spec);
+        let shares = state.shares();
+
+        let shared: f64 = state
+            .receipts
+            .iter()
+            .filter(|r| !shares.sharers(r.id).is_empty())
+            .map(|r| f64::from(r.total(&state)))
+            .sum();
+        let total = f64::from(state.total());
+        let participants: f64 = state
+            .participants
+            .iter()
+            .map(|p| f64::from(p.share(&state)))
+            .sum();
+        prop_assert!(close(total, shared), "{total} != {shared}");
+        prop_assert!(close(participants, total), "{participants} != {total}");
+
+        // Every cent paid is owed by someone
+        prop_assert_eq!(state.balances().values().sum::<i64>(), 0);
+    }
+
+    /// Items excluded for more than the receipt cost leave it at zero
+    /// rather than owing anyone money back.
+    #[test]
+    fn nothing_is_negative(spec in spec()) {
+        let state = in_order(&spec);
+
+        for (r, (total, items)) in spec.receipts.iter().enumerate() {
+            let receipt = state.receipts.iter().find(|x| x.id == receipt_id(spec.row(r))).unwrap();
+            let excluded: i64 = items.iter().sum();
+            let expected = f64::from(cents((total - excluded).max(0)));
+            let actual = f64::from(receipt.total(&state));
+            prop_assert!(actual >= 0.);
+            prop_assert!(close(actual, expected), "{actual} != {expected}");
+        }
+        for receipt in state.receipts.iter() {
+            prop_assert!(receipt.total(&state) >= 0.);
+        }
+        for participant in state.participants.iter() {
+            prop_assert!(participant.share(&state) >= 0.);
+        }
+        prop_assert!(state.total() >= 0.);
+    }
+
+    /// The order participants and receipts were added in changes the order
+    /// things are listed in, but not what anyone owes.
+    #[test]
+    fn order_does_not_change_shares(
+        (spec, people, receipts) in reordered()
+    ) {
+        let a = in_order(&spec);
+        let b = build(&spec, &people, &receipts);
+
+        for p in 0..spec.names.len() {
+            let share = |state: &State| {
+                let participant = state
+                    .participants
+                    .iter()
+                    .find(|x| x.id == participant_id(p))
+                    .unwrap();
+                f64::from(participant.share(state))
+            };
+            prop_assert!(close(share(&a), share(&b)), "{} != {}", share(&a), share(&b));
+        }
+        prop_assert!(close(f64::from(a.total()), f64::from(b.total())));
+
+        // Remainder cents go to whoever was added first, so balances only
+        // stay the same when the participants do
+        let c = build(&spec, &(0..spec.names.len()).collect::<Vec<_>>(), &receipts);
+        prop_assert_eq!(a.balances(), c.balances());
+    }
+
+    /// Both exports list the same shares whatever the order, and each
+    /// participant's total is what their receipts add up to.
+    #[test]
+    fn order_does_not_change_exports(
+        (spec, people, receipts) in reordered()
+    ) {
+        let a = in_order(&spec);
+        let b = build(&spec, &people, &receipts);
+
+        let (a_people, a_receipts, a_total) = read_csv(&a.export_csv());
+        let (b_people, b_receipts, b_total) = read_csv(&b.export_csv());
+        prop_assert_eq!(&a_receipts, &b_receipts);
+        prop_assert_eq!(a_people.len(), b_people.len());
+        for (x, y) in a_people.iter().zip(b_people.iter()) {
+            prop_assert_eq!(&x.0, &y.0);
+            prop_assert!(close(x.1, y.1), "{} != {}", x.1, y.1);
+        }
+        prop_assert!(close(a_total, b_total));
+
+        // Each amount is rounded to a cent on its own
+        for (name, amount) in a_people.iter() {
+            let lines: Vec<f64> = a_receipts
+                .iter()
+                .filter(|r| r.0 == *name)
+                .map(|r| r.2.parse().unwrap())
+                .collect();
+            let people = a_people.iter().filter(|p| p.0 == *name).count();
+            let listed: f64 = a_people.iter().filter(|p| p.0 == *name).map(|p| p.1).sum();
+            let sum: f64 = lines.iter().sum();
+            let rounding = 0.005 * (lines.len() + people) as f64 + 0.01;
+            prop_assert!((listed - sum).abs() <= rounding, "{name}: {amount}, {listed} != {sum}");
+        }
+        let listed: f64 = a_people.iter().map(|p| p.1).sum();
+        prop_assert!((listed - a_total).abs() <= 0.005 * (a_people.len() + 1) as f64 + 0.01);
+
+        prop_assert_eq!(read_txt(&a.export_txt()), read_txt(&b.export_txt()));
+    }
+}
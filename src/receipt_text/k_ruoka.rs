@@ -0,0 +1,39 @@
+use super::{parse_lines, Keywords, ParsedReceipt, ReceiptParser};
+
+/// K-Citymarket, K-Supermarket and K-Market receipts from the K-Ruoka app.
+pub struct KRuoka;
+
+const KEYWORDS: Keywords = Keywords {
+    totals: &["YHTEENSÄ", "YHTEENSA", "MAKSETTAVA"],
+    discounts: &[
+        "PLUSSA-ETU",
+        "PLUSSA-KORTTIETU",
+        "ETU",
+        "ALENNUS",
+        "TARJOUS",
+    ],
+    ignored: &["ALV", "VEROTON", "VERO", "PLUSSA-PISTEET"],
+};
+
+impl ReceiptParser for KRuoka {
+    fn name(&self) -> &'static str {
+        "K-Ruoka"
+    }
+
+    fn detect(&self, text: &str) -> bool {
+        let text = text.to_uppercase();
+        [
+            "K-MARKET",
+            "K-SUPERMARKET",
+            "K-CITYMARKET",
+            "K-RUOKA",
+            "PLUSSA",
+        ]
+        .iter()
+        .any(|k| text.contains(k))
+    }
+
+    fn parse(&self, text: &str) -> ParsedReceipt {
+        parse_lines(text, &KEYWORDS)
+    }
+}
@@ -0,0 +1,453 @@
+use crate::locale::Locale;
+use crate::theme::Palette;
+use crate::{Item, Receipt, State};
+
+mod k_ruoka;
+mod s_group;
+
+/// Result of parsing pasted receipt text. Lines between the first item and
+/// the grand total that matched nothing end up in `unparsed`.
+#[derive(Default, Clone, Debug)]
+pub struct ParsedReceipt {
+    pub merchant: Option<String>,
+    pub items: Vec<Item>,
+    pub total: Option<f32>,
+    pub unparsed: Vec<String>,
+}
+
+impl ParsedReceipt {
+    pub fn items_total(&self) -> f32 {
+        self.items.iter().fold(0., |s, i| s + i.total)
+    }
+}
+
+/// A store-specific receipt text format.
+pub trait ReceiptParser {
+    fn name(&self) -> &'static str;
+    /// Whether `text` looks like it came from this store.
+    fn detect(&self, text: &str) -> bool;
+    fn parse(&self, text: &str) -> ParsedReceipt;
+}
+
+/// Every known format, in detection order. Add new stores here.
+pub fn parsers() -> Vec<Box<dyn ReceiptParser>> {
+    vec![Box::new(s_group::SGroup), Box::new(k_ruoka::KRuoka)]
+}
+
+/// Parses `text` with the first parser that recognises it, or the first
+/// parser if none does.
+pub fn parse(text: &str) -> (&'static str, ParsedReceipt) {
+    let parsers = parsers();
+    let parser = parsers
+        .iter()
+        .find(|p| p.detect(text))
+        .unwrap_or(&parsers[0]);
+    (parser.name(), parser.parse(text))
+}
+
+/// Label keywords that classify an amount line. Matched against whole
+/// words of the upper-cased label.
+pub struct Keywords {
+    pub totals: &'static [&'static str],
+    pub discounts: &'static [&'static str],
+    pub ignored: &'static [&'static str],
+}
+
+fn has_keyword(label: &str, keywords: &[&str]) -> bool {
+    label
+        .split_whitespace()
+        .any(|word| keywords.contains(&word))
+}
+
+/// Parses a Finnish money amount: `1,23`, `-0,50`, `0,50-` or `1.23`.
+fn parse_amount(token: &str) -> Option<f32> {
+    let token = token.trim_end_matches('€');
+    let (token, negative) = match (token.strip_prefix('-'), token.strip_suffix('-')) {
+        (Some(t), _) | (_, Some(t)) => (t, true),
+        _ => (token, false),
+    };
+    let (whole, decimals) = token.split_once([',', '.'])?;
+    if whole.is_empty()
+        || decimals.len() != 2
+        || !whole
+            .chars()
+            .chain(decimals.chars())
+            .all(|c| c.is_ascii_digit())
+    {
+        return None;
+    }
+    let amount: f32 = format!("{whole}.{decimals}").parse().ok()?;
+    Some(if negative { -amount } else { amount })
+}
+
+/// Splits the trailing amount off a line, skipping a currency marker and a
+/// single-letter VAT class printed after it.
+fn split_amount(line: &str) -> (String, Option<f32>) {
+    let tokens: Vec<&str> = line.split_whitespace().collect();
+    let mut end = tokens.len();
+    while end > 0 && matches!(tokens[end - 1], "€" | "EUR" | "A" | "B" | "C" | "*") {
+        end -= 1;
+    }
+    match end.checked_sub(1).and_then(|i| parse_amount(tokens[i])) {
+        Some(amount) => (tokens[..end - 1].join(" "), Some(amount)),
+        None => (tokens.join(" "), None),
+    }
+}
+
+/// Recognises quantity lines such as `2 KPL 0,99 €/KPL`, `2 KPL x 0,89 €`
+/// or `0,512 KG 3,99 €/KG`, returning the quantity and the line total when
+/// it can be worked out.
+fn quantity(line: &str) -> Option<(f32, Option<f32>)> {
+    let tokens: Vec<&str> = line.split_whitespace().collect();
+    let first = tokens.first()?.to_uppercase();
+    let number = first.trim_end_matches('X').replace(',', ".");
+    let quantity: f32 = number.parse().ok()?;
+
+    let unit = tokens.get(1).map(|t| t.to_uppercase()).unwrap_or_default();
+    if !first.ends_with('X') && !matches!(unit.as_str(), "KPL" | "KG" | "PKT" | "PS" | "L" | "X") {
+        return None;
+    }
+
+    let amounts: Vec<f32> = tokens[1..]
+        .iter()
+        .filter_map(|t| parse_amount(t.split('/').next().unwrap_or(t)))
+        .collect();
+    let total = match amounts.as_slice() {
+        [.., total] if amounts.len() >= 2 => Some(*total),
+        [unit_price] => Some(quantity * unit_price),
+        _ => None,
+    };
+    Some((quantity, total))
+}
+
+/// Line-oriented parser shared by the Finnish grocery formats: an item is a
+/// label followed by its price, optionally followed by a quantity line and
+/// discount lines, up to the grand total.
+pub fn parse_lines(text: &str, keywords: &Keywords) -> ParsedReceipt {
+    let mut receipt = ParsedReceipt::default();
+    let mut pending: Option<&str> = None;
+
+    fn resolve(receipt: &mut ParsedReceipt, line: &str) {
+        if !receipt.items.is_empty() {
+            receipt.unparsed.push(line.to_string());
+        } else if receipt.merchant.is_none() {
+            receipt.merchant = Some(line.to_string());
+        }
+    }
+
+    for line in text.lines().map(str::trim) {
+        if line.is_empty() || line.chars().all(|c| "-=*_ ".contains(c)) {
+            continue;
+        }
+
+        if let Some((quantity, total)) = quantity(line) {
+            match (pending.take(), total) {
+                // Label on its own line, price on the quantity line
+                (Some(label), Some(total)) => receipt.items.push(Item {
+                    label: label.to_string(),
+                    quantity,
+                    discount: 0.,
+                    total,
+                }),
+                (label, _) => {
+                    if let Some(label) = label {
+                        resolve(&mut receipt, label);
+                    }
+                    match receipt.items.last_mut() {
+                        Some(item) => item.quantity = quantity,
+                        None => receipt.unparsed.push(line.to_string()),
+                    }
+                }
+            }
+            continue;
+        }
+
+        if let Some(label) = pending.take() {
+            resolve(&mut receipt, label);
+        }
+
+        let (label, amount) = split_amount(line);
+        let Some(amount) = amount else {
+            pending = Some(line);
+            continue;
+        };
+
+        let upper = label.to_uppercase();
+        if has_keyword(&upper, keywords.totals) {
+            receipt.total = Some(amount);
+            // Payment and VAT breakdown follow the total
+            break;
+        } else if has_keyword(&upper, keywords.ignored) {
+            continue;
+        } else if amount < 0. || has_keyword(&upper, keywords.discounts) {
+            match receipt.items.last_mut() {
+                Some(item) => {
+                    item.discount += amount.abs();
+                    item.total -= amount.abs();
+                }
+                None => receipt.unparsed.push(line.to_string()),
+            }
+        } else {
+            receipt.items.push(Item {
+                label,
+                quantity: 1.,
+                discount: 0.,
+                total: amount,
+            });
+        }
+    }
+
+    if let Some(label) = pending {
+        resolve(&mut receipt, label);
+    }
+
+    receipt
+}
+
+/// Shows the parsed items, the unparsed lines and how the total checks out.
+/// Returns the total the receipt should be created with.
+pub fn preview(ui: &imgui::Ui, locale: &Locale, palette: &Palette, parsed: &ParsedReceipt) -> f32 {
+    let number = |value: f32, decimals| locale.language.number(f64::from(value), decimals);
+    ui.child_window("##parsed_items")
+        .size([0., 150.])
+        .build(|| {
+            for item in parsed.items.iter() {
+                ui.text(format!(
+                    "{}  {} x {}",
+                    number(item.total, 2),
+                    number(item.quantity, 3),
+                    item.label
+                ));
+                if item.discount > 0. {
+                    ui.same_line();
+                    ui.text_disabled(format!("(-{})", number(item.discount, 2)));
+                }
+            }
+            for line in parsed.unparsed.iter() {
+                ui.text_colored(palette.error, format!("? {line}"));
+            }
+        });
+
+    let items_total = parsed.items_total();
+    match parsed.total {
+        Some(total) if (total - items_total).abs() > 0.005 => ui.text_colored(
+            palette.warning,
+            locale.tr_args(
+                "total-mismatch",
+                &[
+                    ("total", number(total, 2).into()),
+                    ("items", number(items_total, 2).into()),
+                ],
+            ),
+        ),
+        Some(total) => {
+            ui.text(locale.tr_args("total-amount", &[("total", number(total, 2).into())]))
+        }
+        None => ui.text_colored(
+            palette.warning,
+            locale.tr_args("total-missing", &[("items", number(items_total, 2).into())]),
+        ),
+    }
+
+    parsed.total.unwrap_or(items_total)
+}
+
+#[derive(Default)]
+pub struct PasteReceipt {
+    pub open: bool,
+    text: String,
+    label: String,
+    parsed: Option<(&'static str, ParsedReceipt)>,
+}
+
+pub fn window(ui: &imgui::Ui, state: &mut State) {
+    let mut open = state.paste_receipt.open;
+
+    ui.window(format!(
+        "{}###paste_receipt",
+        state.locale.tr("paste-receipt")
+    ))
+    .size([500.0, 420.0], imgui::Condition::FirstUseEver)
+    .position([150., 40.], imgui::Condition::FirstUseEver)
+    .collapsible(false)
+    .opened(&mut open)
+    .build(|| {
+        let locale = &state.locale;
+        let paste = &mut state.paste_receipt;
+
+        ui.text(locale.tr("receipt-text"));
+        if ui.button(locale.tr("paste-from-clipboard")) {
+            if let Some(text) = ui.clipboard_text() {
+                paste.text = text;
+            }
+        }
+        ui.same_line();
+        if ui.button(locale.tr("clear")) {
+            paste.text.clear();
+            paste.parsed = None;
+        }
+        ui.input_text_multiline("##receipt_text", &mut paste.text, [-1., 120.])
+            .build();
+
+        {
+            let _danger_token = ui.begin_disabled(paste.text.trim().is_empty());
+            if ui.button(locale.tr("parse")) {
+                let (name, parsed) = parse(&paste.text);
+                paste.label = parsed.merchant.clone().unwrap_or_default();
+                paste.parsed = Some((name, parsed));
+            }
+        }
+
+        let Some((name, parsed)) = &paste.parsed else {
+            return;
+        };
+
+        ui.text_disabled(locale.tr_args("receipt-format", &[("name", (*name).into())]));
+        ui.text(locale.tr("receipt-label"));
+        ui.input_text("##paste_label", &mut paste.label)
+            .hint(locale.tr_args("example", &[("value", "Prisma Kamppi".into())]))
+            .build();
+
+        let total = preview(ui, locale, &state.settings.palette(), parsed);
+        let _danger_token = ui.begin_disabled(paste.label.is_empty() || total <= 0.);
+        if ui.button(locale.tr("add-receipt")) {
+            let mut receipt = Receipt::new(paste.label.clone(), total, false);
+            receipt.items = parsed.items.clone();
+            paste.text.clear();
+            paste.label.clear();
+            paste.parsed = None;
+            state.add_receipt(receipt);
+        }
+    });
+
+    state.paste_receipt.open = open;
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn amounts() {
+        for (token, amount) in [
+            ("1,23", Some(1.23)),
+            ("1.23", Some(1.23)),
+            ("-0,50", Some(-0.5)),
+            ("0,50-", Some(-0.5)),
+            ("2,49€", Some(2.49)),
+            ("12,5", None),
+            (",50", None),
+            ("1,2a", None),
+            ("KPL", None),
+        ] {
+            assert_eq!(parse_amount(token), amount, "{token}");
+        }
+    }
+
+    /// A receipt, the format it should be read as and what it should give:
+    /// the merchant, every item as label, quantity, discount and total, the
+    /// grand total and the lines left unparsed.
+    type Case = (
+        &'static str,
+        &'static str,
+        &'static str,
+        &'static [(&'static str, f32, f32, f32)],
+        Option<f32>,
+        &'static [&'static str],
+    );
+
+    const CASES: [Case; 3] = [
+        (
+            "PRISMA KAMPPI
+             MAITO 1L            1,29
+             BANAANI
+             0,512 KG 1,99 €/KG  1,02
+             COCA-COLA 1,5L      2,49
+             PANTTI              0,40
+             JUUSTO              5,49
+             S-ETU              -1,00
+             JOGURTTI            1,98
+             2 KPL 0,99 €/KPL
+             ----------------------
+             YHTEENSÄ           11,67
+             ALV 14%             1,43",
+            "S-Group",
+            "PRISMA KAMPPI",
+            &[
+                ("MAITO 1L", 1., 0., 1.29),
+                ("BANAANI", 0.512, 0., 1.02),
+                ("COCA-COLA 1,5L", 1., 0., 2.49),
+                ("PANTTI", 1., 0., 0.4),
+                ("JUUSTO", 1., 1., 4.49),
+                ("JOGURTTI", 2., 0., 1.98),
+            ],
+            Some(11.67),
+            &[],
+        ),
+        (
+            "K-Citymarket Ruoholahti
+             KAURAJUOMA 1L       1,89
+             PLUSSA-ETU          0,30-
+             RUISLEIPÄ           2,15 B
+             TERVETULOA UUDELLEEN
+             PANTTI              0,30
+             2 X 0,15
+             PLUSSA-PISTEET      4,00
+             MAKSETTAVA          4,04 EUR",
+            "K-Ruoka",
+            "K-Citymarket Ruoholahti",
+            &[
+                ("KAURAJUOMA 1L", 1., 0.3, 1.59),
+                ("RUISLEIPÄ", 1., 0., 2.15),
+                ("PANTTI", 2., 0., 0.3),
+            ],
+            Some(4.04),
+            &["TERVETULOA UUDELLEEN"],
+        ),
+        (
+            "Sale Hakaniemi
+             KAHVI 500G          4,99 €
+             ALENNUS             1,00-
+             Yhteensä            3,99 €",
+            "S-Group",
+            "Sale Hakaniemi",
+            &[("KAHVI 500G", 1., 1., 3.99)],
+            Some(3.99),
+            &[],
+        ),
+    ];
+
+    fn close(a: f32, b: f32) -> bool {
+        (a - b).abs() < 0.001
+    }
+
+    #[test]
+    fn receipts() {
+        for (text, format, merchant, items, total, unparsed) in CASES {
+            let (name, parsed) = parse(text);
+            assert_eq!(name, format, "{merchant}");
+            assert_eq!(parsed.merchant.as_deref(), Some(merchant));
+            assert_eq!(
+                parsed.items.len(),
+                items.len(),
+                "{merchant}: {:?}",
+                parsed.items
+            );
+            for (item, (label, quantity, discount, total)) in parsed.items.iter().zip(items) {
+                assert_eq!(item.label, *label, "{merchant}");
+                assert!(
+                    close(item.quantity, *quantity)
+                        && close(item.discount, *discount)
+                        && close(item.total, *total),
+                    "{merchant}: {item:?}"
+                );
+            }
+            assert!(
+                parsed.total.zip(total).is_some_and(|(a, b)| close(a, b)),
+                "{merchant}"
+            );
+            assert!(close(parsed.items_total(), total.unwrap()), "{merchant}");
+            assert_eq!(parsed.unparsed, unparsed, "{merchant}");
+        }
+    }
+}
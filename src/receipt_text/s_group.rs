@@ -0,0 +1,35 @@
+use super::{parse_lines, Keywords, ParsedReceipt, ReceiptParser};
+
+/// Prisma, S-market, Sale and Alepa receipts as shown in the S-mobiili app
+/// and the e-receipt emails.
+pub struct SGroup;
+
+const KEYWORDS: Keywords = Keywords {
+    totals: &["YHTEENSÄ", "YHTEENSA", "MAKSETTAVA"],
+    discounts: &["ALENNUS", "BONUS", "ETU", "S-ETU", "KAMPANJA"],
+    ignored: &["ALV", "VEROTON", "VERO", "PYÖRISTYS"],
+};
+
+impl ReceiptParser for SGroup {
+    fn name(&self) -> &'static str {
+        "S-Group"
+    }
+
+    fn detect(&self, text: &str) -> bool {
+        let text = text.to_uppercase();
+        [
+            "S-MARKET",
+            "PRISMA",
+            "ALEPA",
+            "SALE ",
+            "S-ETUKORTTI",
+            "BONUSTA",
+        ]
+        .iter()
+        .any(|k| text.contains(k))
+    }
+
+    fn parse(&self, text: &str) -> ParsedReceipt {
+        parse_lines(text, &KEYWORDS)
+    }
+}
@@ -0,0 +1,409 @@
+use crate::locale::parse_date;
+use crate::split::weighted;
+use crate::{Receipt, State};
+use chrono::{Days, Months, NaiveDate};
+use serde::{Deserialize, Serialize};
+use std::collections::HashMap;
+use uuid::Uuid;
+
+/// How many upcoming occurrences can be skipped or changed ahead of time.
+const UPCOMING: u32 = 3;
+
+#[derive(Clone, Copy, PartialEq, Eq, Debug, Serialize, Deserialize)]
+pub enum Schedule {
+    Weekly,
+    Monthly,
+    Yearly,
+}
+
+impl Schedule {
+    pub const ALL: [Schedule; 3] = [Schedule::Weekly, Schedule::Monthly, Schedule::Yearly];
+
+    /// Id of the schedule's name in the catalogues.
+    pub fn name(&self) -> &'static str {
+        match self {
+            Schedule::Weekly => "schedule-weekly",
+            Schedule::Monthly => "schedule-monthly",
+            Schedule::Yearly => "schedule-yearly",
+        }
+    }
+}
+
+/// A change to a single occurrence of a template.
+#[derive(Clone, Copy, PartialEq, Debug, Serialize, Deserialize)]
+pub enum Override {
+    Skip,
+    Amount(f32),
+}
+
+/// An expense split the same way every period, e.g. rent.
+#[derive(Clone, Debug, Serialize, Deserialize)]
+pub struct Template {
+    pub id: Uuid,
+    pub label: String,
+    pub amount: f32,
+    pub schedule: Schedule,
+    /// Date of the first occurrence, later ones fall on the same day of the
+    /// week, month or year
+    pub start: NaiveDate,
+    pub weights: Vec<(Uuid, f32)>,
+    pub payer: Option<Uuid>,
+    /// How many occurrences have been turned into receipts
+    pub generated: u32,
+    /// Keyed by occurrence number
+    pub overrides: HashMap<u32, Override>,
+}
+
+impl Template {
+    /// Date of occurrence `n`, counting from zero. Days past the end of a
+    /// shorter month fall on its last day.
+    pub fn occurrence(&self, n: u32) -> Option<NaiveDate> {
+        match self.schedule {
+            Schedule::Weekly => self.start.checked_add_days(Days::new(7 * u64::from(n))),
+            Schedule::Monthly => self.start.checked_add_months(Months::new(n)),
+            Schedule::Yearly => self.start.checked_add_months(Months::new(12 * n)),
+        }
+    }
+
+    fn period(&self, date: NaiveDate) -> String {
+        match self.schedule {
+            Schedule::Weekly => date.format("%Y-%m-%d").to_string(),
+            Schedule::Monthly => date.format("%Y-%m").to_string(),
+            Schedule::Yearly => date.format("%Y").to_string(),
+        }
+    }
+
+    /// What occurrence `n` costs, or `None` if it is skipped. A changed
+    /// amount may be zero or negative, which `generate_recurring` skips.
+    pub fn amount_of(&self, n: u32) -> Option<f32> {
+        match self.overrides.get(&n) {
+            Some(Override::Skip) => None,
+            Some(Override::Amount(amount)) => Some(*amount),
+            None => Some(self.amount),
+        }
+    }
+}
+
+impl State {
+    /// Adds receipts for every occurrence of the recurring templates up to
+    /// and including `today`, catching up on periods the session was not
+    /// open for. Returns how many receipts were added.
+    pub fn generate_recurring(&mut self, today: NaiveDate) -> usize {
+        let mut added = 0;
+
+        for t in 0..self.recurring.len() {
+            loop {
+                let template = &self.recurring[t];
+                let n = template.generated;
+                let Some(date) = template.occurrence(n).filter(|d| *d <= today) else {
+                    break;
+                };
+
+                // Like the form, which takes no amount but a positive one
+                if let Some(amount) = template.amount_of(n).filter(|a| *a > 0.) {
+                    // People removed from the session since are left out
+                    let weights: Vec<(Uuid, f32)> = template
+                        .weights
+                        .iter()
+                        .filter(|(id, _)| self.participants.iter().any(|p| p.id == *id))
+                        .copied()
+                        .collect();
+                    let cents = (amount * 100.).round() as i64;
+                    let parts =
+                        weighted(cents, &weights.iter().map(|(_, w)| *w).collect::<Vec<_>>());
+                    let owed: Vec<(Uuid, i64)> =
+                        weights.iter().map(|(id, _)| *id).zip(parts).collect();
+
+                    let mut receipt = Receipt::new(
+                        format!("{} {}", template.label, template.period(date)),
+                        amount,
+                        false,
+                    );
+                    receipt.payer = template.payer;
+                    receipt.date = Some(date);
+                    self.add_split(receipt, &owed);
+                    added += 1;
+                }
+
+                let template = &mut self.recurring[t];
+                template.overrides.remove(&n);
+                template.generated += 1;
+            }
+        }
+
+        added
+    }
+}
+
+pub struct RecurringUi {
+    pub open: bool,
+    label: String,
+    amount: f32,
+    schedule: usize,
+    start: String,
+    payer: usize,
+    weights: HashMap<Uuid, f32>,
+    /// Receipts added when the session was last opened
+    pub generated: usize,
+}
+
+impl Default for RecurringUi {
+    fn default() -> Self {
+        Self {
+            open: false,
+            label: String::new(),
+            amount: 0.,
+            schedule: 1,
+            start: chrono::Local::now().date_naive().to_string(),
+            payer: 0,
+            weights: HashMap::new(),
+            generated: 0,
+        }
+    }
+}
+
+fn name(state: &State, id: Uuid) -> String {
+    state
+        .participants
+        .iter()
+        .find(|p| p.id == id)
+        .map_or(state.locale.tr("removed"), |p| p.name.clone())
+}
+
+fn templates(ui: &imgui::Ui, state: &mut State) {
+    if state.recurring.is_empty() {
+        ui.text_disabled(state.locale.tr("no-recurring-expenses"));
+    }
+
+    let mut remove = None;
+    for t in 0..state.recurring.len() {
+        let template = &state.recurring[t];
+        let title = format!(
+            "{}: {} {}##template_{}",
+            template.label,
+            state.settings.number(f64::from(template.amount)),
+            state.locale.tr(template.schedule.name()),
+            template.id
+        );
+        let shares = template
+            .weights
+            .iter()
+            .map(|(id, w)| format!("{} x{w}", name(state, *id)))
+            .collect::<Vec<_>>()
+            .join(", ");
+        let payer = template
+            .payer
+            .map_or(state.locale.tr("nobody"), |id| name(state, id));
+
+        ui.tree_node_config(title).build(|| {
+            let locale = &state.locale;
+            let settings = &state.settings;
+            ui.text_disabled(locale.tr_args("paid-by-name", &[("name", payer.into())]));
+            ui.text_wrapped(locale.tr_args("shared-by", &[("names", shares.into())]));
+
+            ui.text(locale.tr("upcoming"));
+            let template = &mut state.recurring[t];
+            for n in template.generated..template.generated + UPCOMING {
+                let Some(date) = template.occurrence(n) else {
+                    continue;
+                };
+                let _id = ui.push_id(format!("{}_{n}", template.id));
+
+                let mut skip = template.overrides.get(&n) == Some(&Override::Skip);
+                if ui.checkbox(
+                    locale.tr_args("skip-date", &[("date", settings.date(date).into())]),
+                    &mut skip,
+                ) {
+                    if skip {
+                        template.overrides.insert(n, Override::Skip);
+                    } else {
+                        template.overrides.remove(&n);
+                    }
+                }
+                if !skip {
+                    ui.same_line();
+                    let mut amount = template.amount_of(n).unwrap_or(template.amount);
+                    ui.set_next_item_width(80.);
+                    if ui.input_float("##amount", &mut amount).build() {
+                        template.overrides.insert(n, Override::Amount(amount));
+                    }
+                    if template.overrides.contains_key(&n) {
+                        ui.same_line();
+                        if ui.small_button(locale.tr("reset")) {
+                            template.overrides.remove(&n);
+                        }
+                    }
+                    if amount <= 0. {
+                        ui.text_colored(
+                            settings.palette().error,
+                            locale.tr("invalid-occurrence-amount"),
+                        );
+                    }
+                }
+            }
+
+            if ui.button(locale.tr("remove")) {
+                remove = Some(t);
+            }
+        });
+    }
+
+    if let Some(t) = remove {
+        state.recurring.remove(t);
+    }
+}
+
+fn form(ui: &imgui::Ui, state: &mut State) {
+    let locale = &state.locale;
+    let mut names = vec![locale.tr("nobody")];
+    names.extend(state.participants.iter().map(|p| p.name.clone()));
+    let form = &mut state.recurring_ui;
+
+    ui.text(locale.tr("new-recurring-expense"));
+    ui.input_text(
+        format!("{}##recurring_label", locale.tr("label")),
+        &mut form.label,
+    )
+    .hint(locale.tr("recurring-label-hint"))
+    .build();
+    ui.input_float(
+        format!("{}##recurring_amount", locale.tr("amount")),
+        &mut form.amount,
+    )
+    .build();
+    let schedules = Schedule::ALL.map(|s| locale.tr(s.name()));
+    ui.combo_simple_string(
+        format!("{}##recurring_schedule", locale.tr("schedule")),
+        &mut form.schedule,
+        &schedules,
+    );
+    ui.input_text(
+        format!("{}##recurring_start", locale.tr("first-date")),
+        &mut form.start,
+    )
+    .hint(locale.tr("date-hint"))
+    .build();
+    form.payer = form.payer.min(names.len() - 1);
+    ui.combo_simple_string(
+        format!("{}##recurring_payer", locale.tr("paid-by")),
+        &mut form.payer,
+        &names,
+    );
+
+    ui.text_disabled(locale.tr("share-weights"));
+    for participant in state.participants.iter() {
+        let weight = form.weights.entry(participant.id).or_insert(1.);
+        ui.input_float(
+            format!("{}##weight_{}", participant.name, participant.id),
+            weight,
+        )
+        .build();
+        *weight = weight.max(0.);
+    }
+
+    let start = parse_date(&form.start);
+    if start.is_none() {
+        ui.text_colored(
+            state.settings.palette().error,
+            locale.tr("invalid-first-date"),
+        );
+    }
+    let weights: Vec<(Uuid, f32)> = state
+        .participants
+        .iter()
+        .map(|p| (p.id, form.weights[&p.id]))
+        .filter(|(_, w)| *w > 0.)
+        .collect();
+
+    let _danger_token = ui.begin_disabled(
+        form.label.trim().is_empty() || form.amount <= 0. || start.is_none() || weights.is_empty(),
+    );
+    if ui.button(locale.tr("add-recurring-expense")) {
+        if let Some(start) = start {
+            let template = Template {
+                id: Uuid::new_v4(),
+                label: form.label.trim().to_string(),
+                amount: form.amount,
+                schedule: Schedule::ALL[form.schedule],
+                start,
+                weights,
+                payer: form.payer.checked_sub(1).map(|i| state.participants[i].id),
+                generated: 0,
+                overrides: HashMap::new(),
+            };
+            form.label.clear();
+            form.amount = 0.;
+            state.recurring.push(template);
+            state.recurring_ui.generated =
+                state.generate_recurring(chrono::Local::now().date_naive());
+        }
+    }
+}
+
+pub fn window(ui: &imgui::Ui, state: &mut State) {
+    let mut open = state.recurring_ui.open;
+
+    ui.window(format!(
+        "{}###recurring",
+        state.locale.tr("recurring-expenses")
+    ))
+    .size([420.0, 440.0], imgui::Condition::FirstUseEver)
+    .position([190., 30.], imgui::Condition::FirstUseEver)
+    .collapsible(false)
+    .opened(&mut open)
+    .build(|| {
+        if state.recurring_ui.generated > 0 {
+            ui.text_disabled(state.locale.tr_args(
+                "recurring-generated",
+                &[("count", state.recurring_ui.generated.into())],
+            ));
+        }
+        templates(ui, state);
+        ui.separator();
+        form(ui, state);
+    });
+
+    state.recurring_ui.open = open;
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::Participant;
+
+    #[test]
+    fn occurrences_without_a_positive_amount_are_skipped() {
+        let mut state = State::default();
+        let participant = Participant::new("Alice".to_string());
+        let id = participant.id;
+        state.add_participant(participant);
+
+        let start = NaiveDate::from_ymd_opt(2026, 1, 1).unwrap();
+        state.recurring.push(Template {
+            id: Uuid::new_v4(),
+            label: "Rent".to_string(),
+            amount: 500.,
+            schedule: Schedule::Monthly,
+            start,
+            weights: vec![(id, 1.)],
+            payer: Some(id),
+            generated: 0,
+            overrides: HashMap::from([
+                (1, Override::Amount(0.)),
+                (2, Override::Amount(-20.)),
+                (3, Override::Amount(450.)),
+            ]),
+        });
+
+        let added = state.generate_recurring(NaiveDate::from_ymd_opt(2026, 4, 30).unwrap());
+        let receipts: Vec<(&str, f32)> = state
+            .receipts
+            .iter()
+            .map(|r| (r.label.as_str(), r.total(&state)))
+            .collect();
+        assert_eq!(added, 2);
+        assert_eq!(receipts, [("Rent 2026-01", 500.), ("Rent 2026-04", 450.)]);
+        assert_eq!(state.recurring[0].generated, 4);
+        assert!(state.recurring[0].overrides.is_empty());
+    }
+}
@@ -0,0 +1,435 @@
+use crate::locale::{Language, Locale, Message};
+use crate::support::{file_system, fonts, Changes};
+use crate::theme::{Palette, Preset, Theme};
+use crate::{accounting, State};
+use anyhow::Result;
+use serde::{Deserialize, Serialize};
+use std::fs;
+use std::rc::Rc;
+use std::time::{Duration, Instant};
+
+const SETTINGS_FILE: &str = "settings.json";
+
+/// How long settings have to stay unchanged before they are written, so that
+/// dragging the window edge does not write on every frame.
+const SAVE_DELAY: Duration = Duration::from_secs(1);
+
+/// Preferences kept between runs in the platform's configuration directory.
+#[derive(Clone, PartialEq, Debug, Serialize, Deserialize)]
+#[serde(default)]
+pub struct Settings {
+    /// Of the interface and the reports, and how they write numbers and dates
+    pub language: Language,
+    /// Whether new receipts are shared with everyone
+    pub auto_share: bool,
+    pub default_category: String,
+    /// ISO 4217 code used in exports
+    pub currency: String,
+    /// Shown after amounts in the interface
+    pub currency_symbol: String,
+    pub accounting_format: accounting::Format,
+    /// Name of the theme in use, one of the presets' keys or a saved theme
+    pub theme: String,
+    /// Themes the user saved
+    pub themes: Vec<Theme>,
+    /// In logical pixels, before scaling
+    pub font_size: f32,
+    /// Scales fonts and all other sizes of the interface
+    pub ui_scale: f32,
+    /// A font file to take the glyphs missing from the bundled font from
+    pub font_path: String,
+    /// Logical size of the OS window
+    pub window_size: [f64; 2],
+    /// Name of the session to open at startup
+    pub last_session: Option<String>,
+}
+
+impl Default for Settings {
+    fn default() -> Self {
+        Self {
+            language: Language::system(),
+            auto_share: false,
+            default_category: crate::default_category(),
+            currency: "EUR".to_string(),
+            currency_symbol: "€".to_string(),
+            accounting_format: accounting::Format::Ledger,
+            theme: Preset::Dark.key().to_string(),
+            themes: Vec::new(),
+            font_size: 13.,
+            ui_scale: 1.,
+            font_path: String::new(),
+            window_size: [800., 500.],
+            last_session: None,
+        }
+    }
+}
+
+impl Settings {
+    pub fn load() -> Self {
+        match file_system::read_config(SETTINGS_FILE) {
+            Ok(settings) => settings.unwrap_or_default(),
+            Err(e) => {
+                eprintln!("Failed to load settings: {e}");
+                Self::default()
+            }
+        }
+    }
+
+    /// Formats `amount` with two decimals and the currency symbol.
+    pub fn money(&self, amount: f64) -> String {
+        self.language
+            .money(&self.language.number(amount, 2), &self.currency_symbol)
+    }
+
+    /// Formats `amount` with two decimals, without a currency.
+    pub fn number(&self, amount: f64) -> String {
+        self.language.number(amount, 2)
+    }
+
+    pub fn date(&self, date: chrono::NaiveDate) -> String {
+        self.language.date(date)
+    }
+
+    /// The theme in use, falling back to the dark preset if it was removed.
+    pub fn theme(&self) -> Theme {
+        self.themes
+            .iter()
+            .find(|t| t.name == self.theme)
+            .cloned()
+            .or_else(|| {
+                Preset::ALL
+                    .into_iter()
+                    .find(|p| p.key() == self.theme)
+                    .map(Theme::built_in)
+            })
+            .unwrap_or_else(|| Theme::built_in(Preset::Dark))
+    }
+
+    pub fn palette(&self) -> Palette {
+        self.theme().palette
+    }
+
+    /// `base` with the theme and scale applied.
+    pub fn style(&self, base: &imgui::Style) -> imgui::Style {
+        let mut style = *base;
+        self.theme().apply(&mut style);
+        style.scale_all_sizes(self.ui_scale);
+        style
+    }
+
+    pub fn font_pixels(&self) -> f32 {
+        self.font_size * self.ui_scale
+    }
+}
+
+/// Reads the font file at `path`.
+pub fn read_font(path: &str) -> Result<Rc<[u8]>> {
+    let data = fs::read(path.trim())?;
+    if !fonts::is_font(&data) {
+        return Err(Message::new("not-a-font").arg("path", path.trim()).into());
+    }
+    Ok(data.into())
+}
+
+#[derive(Default)]
+pub struct Preferences {
+    pub open: bool,
+    /// imgui's style before any settings were applied
+    pub base_style: Option<imgui::Style>,
+    /// Contents of `Settings::font_path`
+    pub extra_font: Option<Rc<[u8]>>,
+    font_path: String,
+    /// Name to save the current theme under
+    theme_name: String,
+    /// When the settings last changed without being saved
+    changed: Option<Instant>,
+    message: Option<String>,
+}
+
+impl State {
+    /// Applies the theme and scale, and rebuilds the fonts if asked to,
+    /// before the next frame.
+    pub fn restyle(&self, changes: &mut Changes, rebuild_fonts: bool) {
+        let style = self
+            .preferences
+            .base_style
+            .map(|base| self.settings.style(&base));
+        let size = self.settings.font_pixels();
+        let extra = self.preferences.extra_font.clone();
+
+        changes.push(move |imgui, renderer| {
+            if let Some(style) = style {
+                *imgui.style_mut() = style;
+            }
+            if rebuild_fonts {
+                if let Err(e) = fonts::reload(imgui, renderer, size, extra.as_deref()) {
+                    eprintln!("Failed to load fonts: {e}");
+                }
+            }
+        });
+    }
+
+    /// Marks the settings as changed, they are saved shortly after.
+    pub fn settings_changed(&mut self) {
+        self.preferences.changed = Some(Instant::now());
+    }
+
+    /// Remembers the window size and writes changed settings once they have
+    /// settled. Called every frame, and asks for one more when they do.
+    pub fn save_settings(&mut self, ui: &imgui::Ui, changes: &mut Changes) {
+        let [width, height] = ui.io().display_size;
+        let size = [f64::from(width), f64::from(height)];
+        if size[0] > 0. && size != self.settings.window_size {
+            self.settings.window_size = size;
+            self.settings_changed();
+        }
+
+        if self
+            .preferences
+            .changed
+            .is_some_and(|changed| changed.elapsed() >= SAVE_DELAY)
+        {
+            self.preferences.changed = None;
+            if let Err(e) = file_system::write_config(SETTINGS_FILE, &self.settings) {
+                self.preferences.message = Some(
+                    self.locale
+                        .tr_args("settings-save-failed", &[("error", e.to_string().into())]),
+                );
+            }
+        }
+        if let Some(changed) = self.preferences.changed {
+            changes.wake_at(changed + SAVE_DELAY);
+        }
+    }
+}
+
+pub fn window(ui: &imgui::Ui, state: &mut State, changes: &mut Changes) {
+    let mut open = state.preferences.open;
+    let categories = state.categories();
+    let before = state.settings.clone();
+    // Whether to restyle, and if the fonts need rebuilding as well
+    let mut restyle = None;
+    let locale = &state.locale;
+
+    ui.window(format!("{}###preferences", locale.tr("preferences")))
+        .size([360.0, 320.0], imgui::Condition::FirstUseEver)
+        .position([220., 80.], imgui::Condition::FirstUseEver)
+        .collapsible(false)
+        .opened(&mut open)
+        .build(|| {
+            let settings = &mut state.settings;
+
+            let languages = Language::ALL.map(|l| l.name());
+            let mut language = Language::ALL
+                .iter()
+                .position(|l| *l == settings.language)
+                .unwrap_or(0);
+            if ui.combo_simple_string(
+                format!("{}##language", locale.tr("language")),
+                &mut language,
+                &languages,
+            ) {
+                settings.language = Language::ALL[language];
+            }
+
+            ui.separator();
+            ui.text(locale.tr("new-receipts"));
+            ui.checkbox(locale.tr("share-with-everyone"), &mut settings.auto_share);
+            let mut category = categories
+                .iter()
+                .position(|c| *c == settings.default_category)
+                .unwrap_or(0);
+            if ui.combo_simple_string(
+                format!("{}##default_category", locale.tr("category")),
+                &mut category,
+                &locale.categories(&categories),
+            ) {
+                settings.default_category = categories[category].clone();
+            }
+
+            ui.separator();
+            ui.text(locale.tr("numbers"));
+            ui.input_text(
+                format!("{}##currency", locale.tr("currency-code")),
+                &mut settings.currency,
+            )
+            .hint(locale.tr_args("example", &[("value", "EUR".into())]))
+            .build();
+            ui.input_text(
+                format!("{}##currency_symbol", locale.tr("currency-symbol")),
+                &mut settings.currency_symbol,
+            )
+            .hint(locale.tr_args("example", &[("value", "€".into())]))
+            .build();
+            ui.text_disabled(
+                locale.tr_args(
+                    "example",
+                    &[(
+                        "value",
+                        format!(
+                            "{}, {}",
+                            settings.money(1234.5),
+                            settings.date(chrono::Local::now().date_naive())
+                        )
+                        .into(),
+                    )],
+                ),
+            );
+
+            let formats = accounting::Format::ALL.map(|f| f.name());
+            let mut format = accounting::Format::ALL
+                .iter()
+                .position(|f| *f == settings.accounting_format)
+                .unwrap_or(0);
+            if ui.combo_simple_string(
+                format!("{}##accounting_format", locale.tr("accounting-format")),
+                &mut format,
+                &formats,
+            ) {
+                settings.accounting_format = accounting::Format::ALL[format];
+            }
+
+            ui.separator();
+            // The presets by key, then the saved themes by name
+            let keys: Vec<String> = Preset::ALL
+                .iter()
+                .map(|p| p.key().to_string())
+                .chain(settings.themes.iter().map(|t| t.name.clone()))
+                .collect();
+            let names: Vec<String> = Preset::ALL
+                .iter()
+                .map(|p| locale.tr(p.name()))
+                .chain(settings.themes.iter().map(|t| t.name.clone()))
+                .collect();
+            let mut theme = keys.iter().position(|k| *k == settings.theme).unwrap_or(0);
+            if ui.combo_simple_string(format!("{}##theme", locale.tr("theme")), &mut theme, &names)
+            {
+                settings.theme = keys[theme].clone();
+                restyle = Some(false);
+            }
+
+            let mut delete = None;
+            if let Some(custom) = settings
+                .themes
+                .iter_mut()
+                .find(|t| t.name == settings.theme)
+            {
+                ui.tree_node_config(format!("{}##theme_colours", locale.tr("colours")))
+                    .build(|| {
+                        let palette = &mut custom.palette;
+                        for (id, color) in [
+                            ("background", &mut custom.background),
+                            ("debt", &mut palette.debt),
+                            ("credit", &mut palette.credit),
+                            ("exclusion", &mut palette.exclusion),
+                            ("warning", &mut palette.warning),
+                            ("error", &mut palette.error),
+                        ] {
+                            if ui.color_edit4(format!("{}##colour_{id}", locale.tr(id)), color) {
+                                restyle = Some(false);
+                            }
+                        }
+                    });
+                if ui.button(locale.tr("delete-theme")) {
+                    delete = Some(custom.preset);
+                }
+            } else {
+                ui.text_disabled(locale.tr("theme-copy-hint"));
+            }
+            if let Some(preset) = delete {
+                let name = std::mem::replace(&mut settings.theme, preset.key().to_string());
+                settings.themes.retain(|t| t.name != name);
+                restyle = Some(false);
+            }
+
+            let name = state.preferences.theme_name.trim().to_string();
+            ui.input_text("##theme_name", &mut state.preferences.theme_name)
+                .hint(locale.tr("theme-name-hint"))
+                .build();
+            ui.same_line();
+            {
+                let taken =
+                    Theme::is_built_in(&name) || settings.themes.iter().any(|t| t.name == name);
+                let _danger_token = ui.begin_disabled(name.is_empty() || taken);
+                if ui.button(locale.tr("save-theme")) {
+                    let mut theme = settings.theme();
+                    theme.name = name.clone();
+                    settings.themes.push(theme);
+                    settings.theme = name;
+                    state.preferences.theme_name.clear();
+                }
+            }
+
+            ui.separator();
+            ui.text(locale.tr("fonts"));
+            ui.slider_config(format!("{}##font_size", locale.tr("font-size")), 8., 32.)
+                .display_format("%.0f px")
+                .build(&mut settings.font_size);
+            if ui.is_item_deactivated_after_edit() {
+                restyle = Some(true);
+            }
+            ui.slider_config(
+                format!("{}##ui_scale", locale.tr("interface-scale")),
+                0.5,
+                3.,
+            )
+            .display_format("%.2fx")
+            .build(&mut settings.ui_scale);
+            if ui.is_item_deactivated_after_edit() {
+                restyle = Some(true);
+            }
+
+            let preferences = &mut state.preferences;
+            if preferences.font_path.is_empty() {
+                preferences.font_path = settings.font_path.clone();
+            }
+            ui.text_disabled(locale.tr("extra-font"));
+            ui.input_text("##font_path", &mut preferences.font_path)
+                .hint(locale.tr_args(
+                    "example",
+                    &[(
+                        "value",
+                        "/usr/share/fonts/noto-cjk/NotoSansCJK-Regular.ttc".into(),
+                    )],
+                ))
+                .build();
+            {
+                let _danger_token = ui.begin_disabled(preferences.font_path.trim().is_empty());
+                if ui.button(locale.tr("load-font")) {
+                    match read_font(&preferences.font_path) {
+                        Ok(data) => {
+                            preferences.extra_font = Some(data);
+                            preferences.message = None;
+                            settings.font_path = preferences.font_path.trim().to_string();
+                            restyle = Some(true);
+                        }
+                        Err(e) => preferences.message = Some(locale.error(&e)),
+                    }
+                }
+            }
+            if preferences.extra_font.is_some() {
+                ui.same_line();
+                if ui.button(locale.tr("remove-font")) {
+                    preferences.extra_font = None;
+                    preferences.font_path.clear();
+                    settings.font_path.clear();
+                    restyle = Some(true);
+                }
+            }
+
+            if let Some(message) = &state.preferences.message {
+                ui.text_colored(state.settings.palette().error, message);
+            }
+        });
+
+    if let Some(rebuild_fonts) = restyle {
+        state.restyle(changes, rebuild_fonts);
+    }
+    if state.settings.language != before.language {
+        state.locale = Locale::new(state.settings.language);
+    }
+    if state.settings != before {
+        state.settings_changed();
+    }
+    state.preferences.open = open;
+}
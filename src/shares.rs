@@ -0,0 +1,128 @@
+use crate::{Receipt, State};
+use std::collections::HashMap;
+use uuid::Uuid;
+
+/// How the receipts of a session divide between its participants, worked
+/// out in one pass over the session whenever it changes. Everything a frame
+/// asks for is then a lookup.
+#[derive(Default)]
+pub struct Shares {
+    /// Who shares each receipt, in the order they were added
+    sharers: HashMap<Uuid, Vec<Uuid>>,
+    /// The sum of the items excluded from each receipt
+    excluded: HashMap<Uuid, f32>,
+    /// The receipt each excluded item came from
+    parents: HashMap<Uuid, Uuid>,
+    /// Where each receipt is in `State::receipts`
+    index: HashMap<Uuid, usize>,
+    /// Each participant's share of the receipts the filter lets through, as
+    /// indices into `State::receipts`
+    shares: HashMap<Uuid, Vec<(usize, f32)>>,
+    /// The sum of each participant's shares
+    sums: HashMap<Uuid, f32>,
+    total: f32,
+}
+
+impl Shares {
+    pub fn new(state: &State) -> Self {
+        let mut shares = Shares {
+            index: state
+                .receipts
+                .iter()
+                .enumerate()
+                .map(|(i, r)| (r.id, i))
+                .collect(),
+            ..Default::default()
+        };
+
+        for participant in state.participants.iter() {
+            for receipt in state.share_map.get(&participant.id).into_iter().flatten() {
+                let sharers = shares.sharers.entry(*receipt).or_default();
+                // A receipt listed twice is still shared once
+                if sharers.last() != Some(&participant.id) {
+                    sharers.push(participant.id);
+                }
+            }
+        }
+
+        for (parent, items) in state.exclusions.iter() {
+            shares
+                .excluded
+                .insert(*parent, items.iter().map(|r| r.total).sum());
+            for item in items.iter() {
+                shares.parents.insert(item.id, *parent);
+            }
+        }
+
+        for (i, receipt) in state.receipts.iter().enumerate() {
+            // Excluded items are judged by the receipt they came from
+            let judged = shares
+                .parent(receipt.id)
+                .filter(|_| receipt.exclusion)
+                .and_then(|parent| shares.index(parent))
+                .map_or(receipt, |i| &state.receipts[i]);
+            let total = shares.total_of(receipt);
+            let Some(sharers) = shares.sharers.get(&receipt.id) else {
+                continue;
+            };
+            if !state.filter.passes(judged) {
+                continue;
+            }
+
+            let share = total / sharers.len() as f32;
+            for participant in sharers.iter() {
+                shares
+                    .shares
+                    .entry(*participant)
+                    .or_default()
+                    .push((i, share));
+                *shares.sums.entry(*participant).or_default() += share;
+                shares.total += share;
+            }
+        }
+
+        shares
+    }
+
+    /// Who shares the receipt `id`, in the order they were added.
+    pub fn sharers(&self, id: Uuid) -> &[Uuid] {
+        self.sharers.get(&id).map_or(&[], |s| s.as_slice())
+    }
+
+    /// `receipt`'s total less the items excluded from it, which are paid
+    /// for separately.
+    pub fn total_of(&self, receipt: &Receipt) -> f32 {
+        if receipt.exclusion {
+            return receipt.total;
+        }
+        let excluded = self.excluded.get(&receipt.id).copied().unwrap_or(0.);
+        f32::max(receipt.total - excluded, 0.)
+    }
+
+    /// The receipt the item `id` was excluded from, if it is an excluded
+    /// item.
+    pub fn parent(&self, id: Uuid) -> Option<Uuid> {
+        self.parents.get(&id).copied()
+    }
+
+    /// Where the receipt `id` is in `State::receipts`.
+    pub fn index(&self, id: Uuid) -> Option<usize> {
+        self.index.get(&id).copied()
+    }
+
+    /// `participant`'s share of each receipt the filter lets through, as
+    /// indices into `State::receipts`.
+    pub fn of(&self, participant: Uuid) -> &[(usize, f32)] {
+        self.shares.get(&participant).map_or(&[], |s| s.as_slice())
+    }
+
+    /// The sum of `participant`'s shares.
+    pub fn sum(&self, participant: Uuid) -> f32 {
+        self.sums.get(&participant).copied().unwrap_or(0.)
+    }
+
+    /// The sum of everyone's shares.
+    pub fn total(&self) -> f32 {
+        self.total
+    }
+}
@@ -0,0 +1,85 @@
+use crate::{Receipt, State};
+use uuid::Uuid;
+
+/// Expresses an unequal split as equal layers: everyone who owes something
+/// shares the smallest amount, the rest is peeled off into items shared by
+/// those who owe more, and so on. Returns (amount in cents, sharers) per
+/// layer, the first being the receipt itself.
+pub fn layers(owed: &[i64]) -> Vec<(i64, Vec<usize>)> {
+    let mut order: Vec<usize> = (0..owed.len()).filter(|i| owed[*i] > 0).collect();
+    order.sort_by_key(|i| owed[*i]);
+
+    let mut layers = Vec::new();
+    let mut level = 0;
+    for (k, i) in order.iter().enumerate() {
+        let step = owed[*i] - level;
+        if step > 0 {
+            let sharers = order[k..].to_vec();
+            layers.push((step * sharers.len() as i64, sharers));
+            level = owed[*i];
+        }
+    }
+    layers
+}
+
+/// Divides `cents` in proportion to `weights`, handing the cents lost to
+/// rounding to the first people with a weight.
+pub fn weighted(cents: i64, weights: &[f32]) -> Vec<i64> {
+    let sum: f32 = weights.iter().filter(|w| **w > 0.).sum();
+    if sum <= 0. {
+        return vec![0; weights.len()];
+    }
+
+    let mut parts: Vec<i64> = weights
+        .iter()
+        .map(|w| (cents as f64 * f64::from(w.max(0.)) / f64::from(sum)).floor() as i64)
+        .collect();
+    let mut left = cents - parts.iter().sum::<i64>();
+    for (part, weight) in parts.iter_mut().zip(weights) {
+        if left == 0 {
+            break;
+        }
+        if *weight > 0. {
+            *part += 1;
+            left -= 1;
+        }
+    }
+    parts
+}
+
+impl State {
+    /// Adds `receipt` so that each participant ends up owing their amount in
+    /// `owed`, using excluded items for the parts not everyone shares. With
+    /// nobody owing anything the receipt is added unshared.
+    pub fn add_split(&mut self, receipt: Receipt, owed: &[(Uuid, i64)]) {
+        self.session_changed();
+        let cents: Vec<i64> = owed.iter().map(|(_, c)| *c).collect();
+        let mut layers = layers(&cents).into_iter();
+        let sharers = layers.next().map(|(_, sharers)| sharers);
+        for i in sharers.unwrap_or_default() {
+            self.share_map
+                .entry(owed[i].0)
+                .or_default()
+                .push(receipt.id);
+        }
+
+        let mut exclusions = Vec::new();
+        for (n, (amount, sharers)) in layers.enumerate() {
+            let item = Receipt::new(
+                format!("{} ({})", receipt.label, n + 2),
+                amount as f32 / 100.,
+                true,
+            );
+            for i in sharers {
+                self.share_map.entry(owed[i].0).or_default().push(item.id);
+            }
+            exclusions.push(item);
+        }
+
+        self.receipts.push(receipt.clone());
+        if !exclusions.is_empty() {
+            self.receipts.extend(exclusions.iter().cloned());
+            self.exclusions.insert(receipt.id, exclusions);
+        }
+    }
+}
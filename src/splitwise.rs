@@ -0,0 +1,392 @@
+use crate::accounting::split_cents;
+use crate::locale::Message;
+use crate::{Participant, Receipt, State};
+use anyhow::Result;
+use chrono::NaiveDate;
+use std::collections::HashMap;
+use std::fs;
+
+const FIXED_COLUMNS: [&str; 5] = ["Date", "Description", "Category", "Cost", "Currency"];
+
+/// One Splitwise expense, with what every person paid and owes in cents.
+#[derive(Clone, Debug)]
+pub struct Expense {
+    pub date: Option<NaiveDate>,
+    pub description: String,
+    pub category: String,
+    pub cost: i64,
+    pub paid: Vec<i64>,
+    pub owed: Vec<i64>,
+}
+
+#[derive(Default, Debug)]
+pub struct Group {
+    pub people: Vec<String>,
+    pub expenses: Vec<Expense>,
+    pub warnings: Vec<Message>,
+}
+
+fn cents(value: &str) -> Result<i64> {
+    let value = value.trim();
+    if value.is_empty() {
+        return Ok(0);
+    }
+    let amount: f64 = value
+        .parse()
+        .map_err(|_| Message::new("invalid-amount").arg("amount", value))?;
+    Ok((amount * 100.).round() as i64)
+}
+
+/// Reads a Splitwise group export. Besides the standard layout, where each
+/// person's column holds their net balance change, a `<name> paid` /
+/// `<name> owed` column pair per person is understood.
+pub fn parse(input: &str) -> Result<Group> {
+    let mut reader = csv::ReaderBuilder::new()
+        .flexible(true)
+        .from_reader(input.as_bytes());
+    let headers: Vec<String> = reader
+        .headers()?
+        .iter()
+        .map(|h| h.trim().to_string())
+        .collect();
+
+    if headers.len() < FIXED_COLUMNS.len()
+        || !headers
+            .iter()
+            .zip(FIXED_COLUMNS)
+            .all(|(h, f)| h.eq_ignore_ascii_case(f))
+    {
+        let message = Message::new("not-splitwise-export").arg("columns", FIXED_COLUMNS.join(","));
+        return Err(message.into());
+    }
+
+    let person_columns = &headers[FIXED_COLUMNS.len()..];
+    let split = person_columns.iter().all(|h| {
+        let h = h.to_lowercase();
+        h.ends_with(" paid") || h.ends_with(" owed")
+    });
+
+    let mut group = Group::default();
+    // Column index of (paid, owed) or (net, net) per person
+    let mut columns: Vec<(usize, usize)> = Vec::new();
+    for (i, header) in person_columns.iter().enumerate() {
+        let i = i + FIXED_COLUMNS.len();
+        let name = if split {
+            header[..header.len() - " paid".len()].to_string()
+        } else {
+            header.clone()
+        };
+        match group.people.iter().position(|p| *p == name) {
+            Some(p) if header.to_lowercase().ends_with(" owed") => columns[p].1 = i,
+            Some(p) => columns[p].0 = i,
+            None => {
+                group.people.push(name);
+                columns.push((i, i));
+            }
+        }
+    }
+
+    let mut payments: usize = 0;
+    for record in reader.records() {
+        let record = record?;
+        let field = |i: usize| record.get(i).unwrap_or_default().trim();
+
+        let description = field(1);
+        if description.is_empty() || description.eq_ignore_ascii_case("Total balance") {
+            continue;
+        }
+        if field(2).eq_ignore_ascii_case("Payment") {
+            payments += 1;
+            continue;
+        }
+
+        let cost = cents(field(3))?;
+        let (paid, owed) = if split {
+            let paid = columns
+                .iter()
+                .map(|(p, _)| cents(field(*p)))
+                .collect::<Result<Vec<_>>>()?;
+            let owed = columns
+                .iter()
+                .map(|(_, o)| cents(field(*o)))
+                .collect::<Result<Vec<_>>>()?;
+            (paid, owed)
+        } else {
+            let net = columns
+                .iter()
+                .map(|(n, _)| cents(field(*n)))
+                .collect::<Result<Vec<_>>>()?;
+            // With net columns only a single payer can be recovered: the one
+            // who came out ahead fronted the whole cost
+            let Some(payer) = (0..net.len())
+                .filter(|i| net[*i] > 0)
+                .max_by_key(|i| net[*i])
+            else {
+                group
+                    .warnings
+                    .push(Message::new("splitwise-no-payer").arg("description", description));
+                continue;
+            };
+            let paid = (0..net.len())
+                .map(|i| if i == payer { cost } else { 0 })
+                .collect();
+            let owed = (0..net.len())
+                .map(|i| if i == payer { cost - net[i] } else { -net[i] })
+                .collect();
+            (paid, owed)
+        };
+
+        if owed.iter().sum::<i64>() != cost || paid.iter().sum::<i64>() != cost {
+            group
+                .warnings
+                .push(Message::new("splitwise-does-not-add-up").arg("description", description));
+            continue;
+        }
+        if paid.iter().filter(|p| **p > 0).count() > 1 {
+            group
+                .warnings
+                .push(Message::new("splitwise-several-payers").arg("description", description));
+        }
+
+        group.expenses.push(Expense {
+            date: NaiveDate::parse_from_str(field(0).get(..10).unwrap_or(field(0)), "%Y-%m-%d")
+                .ok(),
+            description: description.to_string(),
+            category: field(2).to_string(),
+            cost,
+            paid,
+            owed,
+        });
+    }
+
+    if payments > 0 {
+        group
+            .warnings
+            .push(Message::new("splitwise-payments-skipped").arg("count", payments));
+    }
+
+    Ok(group)
+}
+
+fn participant_id(state: &mut State, name: &str) -> uuid::Uuid {
+    if let Some(p) = state.participants.iter().find(|p| p.name == name) {
+        return p.id;
+    }
+    let p = Participant::new(name.to_string());
+    let id = p.id;
+    state.workspace.remember(&p);
+    state.session_changed();
+    state.participants.push(p);
+    state.share_map.insert(id, Vec::new());
+    id
+}
+
+/// Adds the group's people and expenses to `state`.
+pub fn apply(state: &mut State, group: &Group) {
+    let ids: Vec<uuid::Uuid> = group
+        .people
+        .iter()
+        .map(|name| participant_id(state, name))
+        .collect();
+
+    for expense in group.expenses.iter() {
+        let mut receipt = Receipt::new(
+            expense.description.clone(),
+            expense.cost as f32 / 100.,
+            false,
+        );
+        receipt.payer = (0..ids.len())
+            .max_by_key(|i| expense.paid[*i])
+            .map(|i| ids[i]);
+        receipt.date = expense.date;
+        if !expense.category.is_empty() {
+            receipt.category = expense.category.clone();
+        }
+
+        let owed: Vec<(uuid::Uuid, i64)> = ids.iter().copied().zip(expense.owed.clone()).collect();
+        state.add_split(receipt, &owed);
+    }
+}
+
+/// Writes the session in Splitwise's export layout, one row per receipt that
+/// passes the filter with each person's net balance change.
+pub fn export(state: &State, date: &str) -> (String, Vec<Message>) {
+    let mut warnings = Vec::new();
+    let mut writer = csv::Writer::from_writer(Vec::new());
+
+    let mut header: Vec<String> = FIXED_COLUMNS.iter().map(|c| c.to_string()).collect();
+    header.extend(state.participants.iter().map(|p| p.name.clone()));
+    let _ = writer.write_record(&header);
+
+    let index: HashMap<uuid::Uuid, usize> = state
+        .participants
+        .iter()
+        .enumerate()
+        .map(|(i, p)| (p.id, i))
+        .collect();
+    let mut balance = vec![0i64; state.participants.len()];
+    let money = |cents: i64| format!("{:.2}", cents as f64 / 100.);
+
+    for receipt in state
+        .receipts
+        .iter()
+        .filter(|r| !r.exclusion && state.filter.matches(state, r))
+    {
+        let Some(payer) = receipt.payer.and_then(|id| index.get(&id).copied()) else {
+            warnings.push(
+                Message::new("splitwise-no-payer").arg("description", receipt.label.as_str()),
+            );
+            continue;
+        };
+
+        let mut owed = vec![0i64; state.participants.len()];
+        let items = state.exclusions.get(&receipt.id);
+        let parts = std::iter::once((receipt, receipt.total(state)))
+            .chain(items.into_iter().flatten().map(|e| (e, e.total(state))));
+        for (part, total) in parts {
+            let sharers = state.sharers(part);
+            let amounts = split_cents((total * 100.).round() as i64, sharers.len());
+            for (p, cents) in sharers.iter().zip(amounts) {
+                owed[index[&p.id]] += cents;
+            }
+        }
+
+        let cost: i64 = owed.iter().sum();
+        if cost != (receipt.total * 100.).round() as i64 {
+            warnings.push(
+                Message::new("splitwise-not-fully-shared")
+                    .arg("label", receipt.label.as_str())
+                    .arg("amount", money(cost)),
+            );
+        }
+
+        let mut row = vec![
+            receipt.date.map_or(date.to_string(), |d| d.to_string()),
+            receipt.label.clone(),
+            receipt.category.clone(),
+            money(cost),
+            state.settings.currency.clone(),
+        ];
+        for (i, owed) in owed.iter().enumerate() {
+            let net = if i == payer { cost - owed } else { -owed };
+            balance[i] += net;
+            row.push(money(net));
+        }
+        let _ = writer.write_record(&row);
+    }
+
+    let mut total = vec![
+        date.to_string(),
+        "Total balance".to_string(),
+        String::new(),
+        String::new(),
+        state.settings.currency.clone(),
+    ];
+    total.extend(balance.into_iter().map(money));
+    let _ = writer.write_record(&total);
+
+    let output = writer
+        .into_inner()
+        .map(|bytes| String::from_utf8_lossy(&bytes).into_owned())
+        .unwrap_or_default();
+    (output, warnings)
+}
+
+#[derive(Default)]
+pub struct Splitwise {
+    pub open: bool,
+    import_path: String,
+    export_path: String,
+    messages: Vec<String>,
+}
+
+pub fn window(ui: &imgui::Ui, state: &mut State) {
+    let mut open = state.splitwise.open;
+
+    ui.window("Splitwise###splitwise")
+        .size([420.0, 260.0], imgui::Condition::FirstUseEver)
+        .position([190., 100.], imgui::Condition::FirstUseEver)
+        .collapsible(false)
+        .opened(&mut open)
+        .build(|| {
+            let locale = &state.locale;
+            let splitwise = &mut state.splitwise;
+
+            ui.text(locale.tr("splitwise-import"));
+            ui.input_text("##splitwise_import", &mut splitwise.import_path)
+                .hint(locale.tr_args(
+                    "example",
+                    &[("value", "flat-2b_2023-05-01_export.csv".into())],
+                ))
+                .build();
+            ui.same_line();
+            {
+                let _danger_token = ui.begin_disabled(splitwise.import_path.is_empty());
+                if ui.button(locale.tr("import")) {
+                    let group = fs::read_to_string(&splitwise.import_path)
+                        .map_err(anyhow::Error::from)
+                        .and_then(|input| parse(&input));
+                    match group {
+                        Ok(group) => {
+                            state.splitwise.messages = group
+                                .warnings
+                                .iter()
+                                .map(|w| state.locale.message(w))
+                                .collect();
+                            state.splitwise.messages.insert(
+                                0,
+                                state.locale.tr_args(
+                                    "splitwise-imported",
+                                    &[
+                                        ("expenses", group.expenses.len().into()),
+                                        ("people", group.people.len().into()),
+                                    ],
+                                ),
+                            );
+                            apply(state, &group);
+                        }
+                        Err(e) => state.splitwise.messages = vec![state.locale.error(&e)],
+                    }
+                }
+            }
+
+            let locale = &state.locale;
+            let splitwise = &mut state.splitwise;
+            ui.text(locale.tr("splitwise-export"));
+            if state.filter.is_active() {
+                ui.text_disabled(locale.tr("showing-filtered"));
+            }
+            ui.input_text("##splitwise_export", &mut splitwise.export_path)
+                .hint(locale.tr_args("example", &[("value", "splitwise.csv".into())]))
+                .build();
+            ui.same_line();
+            {
+                let _danger_token = ui.begin_disabled(splitwise.export_path.is_empty());
+                if ui.button(locale.tr("export")) {
+                    let date = chrono::Local::now().date_naive().to_string();
+                    let (output, warnings) = export(state, &date);
+                    let mut messages: Vec<String> =
+                        warnings.iter().map(|w| state.locale.message(w)).collect();
+                    let splitwise = &mut state.splitwise;
+                    match fs::write(&splitwise.export_path, output) {
+                        Ok(()) => messages.insert(
+                            0,
+                            state.locale.tr_args(
+                                "wrote-file",
+                                &[("path", splitwise.export_path.as_str().into())],
+                            ),
+                        ),
+                        Err(e) => messages.insert(0, e.to_string()),
+                    }
+                    splitwise.messages = messages;
+                }
+            }
+
+            ui.separator();
+            for message in state.splitwise.messages.iter() {
+                ui.text_wrapped(message);
+            }
+        });
+
+    state.splitwise.open = open;
+}
@@ -0,0 +1,401 @@
+use crate::locale::Language;
+use crate::{Receipt, State};
+use imgui::StyleColor;
+use std::collections::BTreeMap;
+use std::f32::consts::TAU;
+use std::fs;
+
+const COLORS: [[f32; 4]; 8] = [
+    [0.26, 0.59, 0.98, 1.],
+    [0.98, 0.55, 0.24, 1.],
+    [0.36, 0.78, 0.42, 1.],
+    [0.90, 0.33, 0.38, 1.],
+    [0.62, 0.47, 0.85, 1.],
+    [0.55, 0.42, 0.33, 1.],
+    [0.89, 0.47, 0.76, 1.],
+    [0.60, 0.60, 0.60, 1.],
+];
+
+const CHART_HEIGHT: f32 = 180.;
+
+/// Every share the filter lets through, with the receipt it is a share of.
+/// Excluded items are judged by the receipt they were excluded from. Built
+/// from the same shares as `per_participant`, so that every chart adds up
+/// to the same total and unshared receipts count in none of them.
+fn shared(state: &State) -> Vec<(&Receipt, f32)> {
+    let shares = state.shares();
+    state
+        .participants
+        .iter()
+        .flat_map(|p| shares.of(p.id).iter())
+        .map(|(i, share)| {
+            let receipt = &state.receipts[*i];
+            (state.parent_of(receipt).unwrap_or(receipt), *share)
+        })
+        .collect()
+}
+
+/// Money shared per category. Excluded items count towards the category of
+/// the receipt they were excluded from.
+pub fn per_category(state: &State) -> Vec<(String, f32)> {
+    let mut totals: Vec<(String, f32)> = Vec::new();
+    for (receipt, share) in shared(state) {
+        match totals.iter_mut().find(|(c, _)| *c == receipt.category) {
+            Some((_, sum)) => *sum += share,
+            None => totals.push((receipt.category.clone(), share)),
+        }
+    }
+    totals.retain(|(_, total)| *total > 0.);
+    totals.sort_by(|a, b| b.1.total_cmp(&a.1));
+    totals
+}
+
+/// Every participant's share, in the order they were added.
+pub fn per_participant(state: &State) -> Vec<(String, f32)> {
+    let shares = state.shares();
+    state
+        .participants
+        .iter()
+        .map(|p| (p.name.clone(), shares.sum(p.id)))
+        .collect()
+}
+
+/// Money shared per month, leaving out receipts without a date.
+pub fn per_month(state: &State) -> Vec<(String, f32)> {
+    let mut months: BTreeMap<String, f32> = BTreeMap::new();
+    for (receipt, share) in shared(state) {
+        if let Some(date) = receipt.date {
+            *months.entry(date.format("%Y-%m").to_string()).or_default() += share;
+        }
+    }
+    months.into_iter().collect()
+}
+
+/// Money spent per category, named in the interface's language.
+fn per_category_named(state: &State) -> Vec<(String, f32)> {
+    per_category(state)
+        .into_iter()
+        .map(|(category, total)| (state.locale.category(&category), total))
+        .collect()
+}
+
+/// The numbers behind every chart, one row per bar, slice or point.
+pub fn to_csv(state: &State) -> String {
+    let locale = &state.locale;
+    let language = state.settings.language;
+    let mut writer = csv::WriterBuilder::new()
+        .delimiter(language.csv_delimiter())
+        .from_writer(Vec::new());
+    let _ = writer.write_record([
+        locale.tr("breakdown"),
+        locale.tr("key"),
+        locale.tr("amount"),
+    ]);
+
+    let breakdowns = [
+        (locale.tr("category"), per_category_named(state)),
+        (locale.tr("participant"), per_participant(state)),
+        (locale.tr("month"), per_month(state)),
+    ];
+    for (breakdown, data) in breakdowns.iter() {
+        for (key, amount) in data.iter() {
+            let amount = language.plain_number(f64::from(*amount), 2);
+            let _ = writer.write_record([breakdown, key, &amount]);
+        }
+    }
+
+    writer
+        .into_inner()
+        .map(|bytes| String::from_utf8_lossy(&bytes).into_owned())
+        .unwrap_or_default()
+}
+
+/// Reserves `size` for a chart, returning its top-left corner and the mouse
+/// position if the chart is hovered.
+fn canvas(ui: &imgui::Ui, id: &str, size: [f32; 2]) -> ([f32; 2], Option<[f32; 2]>) {
+    let origin = ui.cursor_screen_pos();
+    // Zero sized buttons trip an assertion in imgui
+    ui.invisible_button(id, [size[0].max(1.), size[1]]);
+    let mouse = ui.is_item_hovered().then(|| ui.io().mouse_pos);
+    (origin, mouse)
+}
+
+fn bar_chart(ui: &imgui::Ui, language: Language, id: &str, data: &[(String, f32)]) {
+    let width = ui.content_region_avail()[0];
+    let (origin, mouse) = canvas(ui, id, [width, CHART_HEIGHT]);
+    let max = data.iter().map(|(_, v)| *v).fold(0., f32::max);
+    if data.is_empty() || max <= 0. {
+        return;
+    }
+
+    let draw_list = ui.get_window_draw_list();
+    let text = ui.style_color(StyleColor::Text);
+    let bottom = origin[1] + CHART_HEIGHT - ui.text_line_height();
+    let slot = width / data.len() as f32;
+
+    for (i, (label, value)) in data.iter().enumerate() {
+        let left = origin[0] + slot * i as f32 + slot * 0.15;
+        let right = left + slot * 0.7;
+        let top = bottom - (bottom - origin[1]) * value.max(0.) / max;
+        draw_list
+            .add_rect([left, top], [right, bottom], COLORS[i % COLORS.len()])
+            .filled(true)
+            .build();
+
+        let mut label = label.clone();
+        while label.len() > 1 && ui.calc_text_size(&label)[0] > slot {
+            label.pop();
+        }
+        draw_list.add_text([left, bottom], text, label);
+
+        if mouse.is_some_and(|[x, _]| x >= left && x <= right) {
+            ui.tooltip_text(format!(
+                "{}: {}",
+                data[i].0,
+                language.number(f64::from(*value), 2)
+            ));
+        }
+    }
+}
+
+fn pie_chart(ui: &imgui::Ui, language: Language, id: &str, data: &[(String, f32)]) {
+    let width = ui.content_region_avail()[0];
+    let (origin, mouse) = canvas(ui, id, [width, CHART_HEIGHT]);
+    let total: f32 = data.iter().map(|(_, v)| v.max(0.)).sum();
+    if total <= 0. {
+        return;
+    }
+
+    let draw_list = ui.get_window_draw_list();
+    let text = ui.style_color(StyleColor::Text);
+    let radius = CHART_HEIGHT / 2.;
+    let center = [origin[0] + radius, origin[1] + radius];
+    let point = |angle: f32| {
+        [
+            center[0] + radius * angle.cos(),
+            center[1] + radius * angle.sin(),
+        ]
+    };
+
+    // Angle of the mouse measured the same way as the slices, from 0 to TAU
+    let hovered_angle = mouse.and_then(|[x, y]| {
+        let (dx, dy) = (x - center[0], y - center[1]);
+        (dx * dx + dy * dy <= radius * radius).then(|| dy.atan2(dx).rem_euclid(TAU))
+    });
+
+    let mut start = 0.;
+    for (i, (label, value)) in data.iter().enumerate() {
+        let sweep = TAU * value.max(0.) / total;
+        let amount = language.number(f64::from(*value), 2);
+        let color = COLORS[i % COLORS.len()];
+        let segments = (sweep / 0.1).ceil().max(1.) as usize;
+        for s in 0..segments {
+            let a = start + sweep * s as f32 / segments as f32;
+            let b = start + sweep * (s + 1) as f32 / segments as f32;
+            draw_list
+                .add_triangle(center, point(a), point(b), color)
+                .filled(true)
+                .build();
+        }
+
+        let legend = [
+            origin[0] + CHART_HEIGHT + 16.,
+            origin[1] + i as f32 * ui.text_line_height(),
+        ];
+        if legend[1] + ui.text_line_height() <= origin[1] + CHART_HEIGHT {
+            draw_list
+                .add_rect(legend, [legend[0] + 10., legend[1] + 10.], color)
+                .filled(true)
+                .build();
+            let percent = language.number(f64::from(100. * value / total), 0);
+            draw_list.add_text(
+                [legend[0] + 16., legend[1] - 2.],
+                text,
+                format!("{label} {amount} ({percent}%)"),
+            );
+        }
+
+        if hovered_angle.is_some_and(|angle| angle >= start && angle < start + sweep) {
+            ui.tooltip_text(format!("{label}: {amount}"));
+        }
+        start += sweep;
+    }
+}
+
+fn line_chart(ui: &imgui::Ui, language: Language, id: &str, data: &[(String, f32)]) {
+    let width = ui.content_region_avail()[0];
+    let (origin, mouse) = canvas(ui, id, [width, CHART_HEIGHT]);
+    let max = data.iter().map(|(_, v)| *v).fold(0., f32::max);
+    if data.is_empty() || max <= 0. {
+        return;
+    }
+
+    let draw_list = ui.get_window_draw_list();
+    let text = ui.style_color(StyleColor::Text);
+    let axis = ui.style_color(StyleColor::Border);
+    let line_height = ui.text_line_height();
+    let bottom = origin[1] + CHART_HEIGHT - line_height;
+    let top = origin[1] + line_height;
+    let step = width / data.len() as f32;
+
+    draw_list
+        .add_line([origin[0], bottom], [origin[0] + width, bottom], axis)
+        .build();
+    draw_list.add_text(origin, text, language.number(f64::from(max), 2));
+
+    let points: Vec<[f32; 2]> = data
+        .iter()
+        .enumerate()
+        .map(|(i, (_, value))| {
+            [
+                origin[0] + step * (i as f32 + 0.5),
+                bottom - (bottom - top) * value.max(0.) / max,
+            ]
+        })
+        .collect();
+    if points.len() > 1 {
+        draw_list
+            .add_polyline(points.clone(), COLORS[0])
+            .thickness(2.)
+            .build();
+    }
+
+    // Label as many months as fit without overlapping
+    let label_width = ui.calc_text_size("0000-00")[0] + 8.;
+    let every = (label_width / step).ceil().max(1.) as usize;
+    for (i, ((label, value), point)) in data.iter().zip(points.iter()).enumerate() {
+        draw_list
+            .add_circle(*point, 3., COLORS[0])
+            .filled(true)
+            .build();
+        if i % every == 0 {
+            let x = point[0] - ui.calc_text_size(label)[0] / 2.;
+            draw_list.add_text([x, bottom], text, label);
+        }
+        if mouse.is_some_and(|[x, _]| (x - point[0]).abs() <= step / 2.) {
+            ui.tooltip_text(format!(
+                "{label}: {}",
+                language.number(f64::from(*value), 2)
+            ));
+        }
+    }
+}
+
+#[derive(Default)]
+pub struct Statistics {
+    pub open: bool,
+    export_path: String,
+    message: Option<String>,
+}
+
+pub fn window(ui: &imgui::Ui, state: &mut State) {
+    let mut open = state.statistics.open;
+
+    ui.window(format!("{}###statistics", state.locale.tr("statistics")))
+        .size([520.0, 340.0], imgui::Condition::FirstUseEver)
+        .position([140., 60.], imgui::Condition::FirstUseEver)
+        .collapsible(false)
+        .opened(&mut open)
+        .build(|| {
+            let locale = &state.locale;
+            let language = state.settings.language;
+            if state.filter.is_active() {
+                ui.text_disabled(locale.tr("showing-filtered"));
+            }
+
+            if let Some(_tab_bar) = ui.tab_bar("##statistics") {
+                if let Some(_tab) =
+                    ui.tab_item(format!("{}###by_category", locale.tr("by-category")))
+                {
+                    pie_chart(ui, language, "##category_chart", &per_category_named(state));
+                }
+                if let Some(_tab) =
+                    ui.tab_item(format!("{}###by_participant", locale.tr("by-participant")))
+                {
+                    bar_chart(ui, language, "##participant_chart", &per_participant(state));
+                }
+                if let Some(_tab) = ui.tab_item(format!("{}###over_time", locale.tr("over-time"))) {
+                    let months = per_month(state);
+                    if months.is_empty() {
+                        ui.text_disabled(locale.tr("no-dated-receipts"));
+                    }
+                    line_chart(ui, language, "##month_chart", &months);
+                }
+            }
+
+            ui.separator();
+            ui.input_text("##statistics_export", &mut state.statistics.export_path)
+                .hint(locale.tr_args("example", &[("value", "statistics.csv".into())]))
+                .build();
+            ui.same_line();
+            {
+                let path = &state.statistics.export_path;
+                let _danger_token = ui.begin_disabled(path.is_empty());
+                if ui.button(locale.tr("export-csv-file")) {
+                    let message = match fs::write(path, to_csv(state)) {
+                        Ok(()) => locale.tr_args("wrote-file", &[("path", path.as_str().into())]),
+                        Err(e) => e.to_string(),
+                    };
+                    state.statistics.message = Some(message);
+                }
+            }
+            ui.same_line();
+            if ui.button(locale.tr("copy")) {
+                ui.set_clipboard_text(to_csv(state));
+            }
+
+            if let Some(message) = &state.statistics.message {
+                ui.text_wrapped(message);
+            }
+        });
+
+    state.statistics.open = open;
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::Participant;
+    use chrono::NaiveDate;
+
+    #[test]
+    fn charts_add_up_to_the_shares() {
+        let mut state = State::default();
+        let alice = Participant::new("Alice".to_string());
+        let bob = Participant::new("Bob".to_string());
+        let ids = [alice.id, bob.id];
+        state.add_participant(alice);
+        state.add_participant(bob);
+
+        let receipt = |label: &str, total: f32, category: &str| {
+            let mut receipt = Receipt::new(label.to_string(), total, false);
+            receipt.category = category.to_string();
+            receipt.date = NaiveDate::from_ymd_opt(2026, 3, 14);
+            receipt
+        };
+        let groceries = receipt("Groceries", 30., "Groceries");
+        let taxi = receipt("Taxi", 12., "Transport");
+        let deposit = receipt("Deposit", 500., "Housing");
+        for id in ids {
+            state.set_shared(id, groceries.id, true);
+        }
+        state.set_shared(ids[0], taxi.id, true);
+        for receipt in [groceries, taxi, deposit] {
+            state.add_receipt(receipt);
+        }
+
+        assert_eq!(state.total(), 42.);
+        assert_eq!(
+            per_participant(&state),
+            [("Alice".to_string(), 27.), ("Bob".to_string(), 15.)]
+        );
+        assert_eq!(
+            per_category(&state),
+            [
+                ("Groceries".to_string(), 30.),
+                ("Transport".to_string(), 12.)
+            ]
+        );
+        assert_eq!(per_month(&state), [("2026-03".to_string(), 42.)]);
+    }
+}
@@ -1,11 +1,76 @@
-use anyhow::Result;
+use anyhow::{anyhow, Result};
+use serde::de::DeserializeOwned;
+use serde::Serialize;
 use std::fs;
-use std::path::Path;
+use std::path::{Path, PathBuf};
 
-fn confirm_path(path: &str) -> Result<()> {
-    if Path::new(path).exists() {
+const DATA_DIR: &str = "data";
+const APP_DIR: &str = "imgui-test";
+
+fn confirm_path(path: &Path) -> Result<()> {
+    if path.exists() {
         return Ok(());
     }
-    fs::create_dir(path)?;
+    fs::create_dir_all(path)?;
+    Ok(())
+}
+
+fn read<T: DeserializeOwned>(path: &Path) -> Result<Option<T>> {
+    if !path.exists() {
+        return Ok(None);
+    }
+    Ok(Some(serde_json::from_str(&fs::read_to_string(path)?)?))
+}
+
+fn write<T: Serialize>(path: &Path, value: &T) -> Result<()> {
+    if let Some(parent) = path.parent() {
+        confirm_path(parent)?;
+    }
+    fs::write(path, serde_json::to_string_pretty(value)?)?;
+    Ok(())
+}
+
+/// Path of `name` in the platform's configuration directory, e.g.
+/// `~/.config/imgui-test` on Linux, which is created if missing.
+pub fn config_path(name: &str) -> Result<PathBuf> {
+    let dir = dirs::config_dir()
+        .ok_or_else(|| anyhow!("No configuration directory on this platform"))?
+        .join(APP_DIR);
+    confirm_path(&dir)?;
+    Ok(dir.join(name))
+}
+
+/// Reads `name` from the data directory, `None` if it hasn't been written yet.
+pub fn read_json<T: DeserializeOwned>(name: &str) -> Result<Option<T>> {
+    read(&Path::new(DATA_DIR).join(name))
+}
+
+pub fn write_json<T: Serialize>(name: &str, value: &T) -> Result<()> {
+    write(&Path::new(DATA_DIR).join(name), value)
+}
+
+/// Reads `name` from the data directory as text, for files that need more
+/// than deserializing, `None` if it hasn't been written yet.
+pub fn read_data(name: &str) -> Result<Option<String>> {
+    let path = Path::new(DATA_DIR).join(name);
+    if !path.exists() {
+        return Ok(None);
+    }
+    Ok(Some(fs::read_to_string(path)?))
+}
+
+pub fn write_data(name: &str, contents: &str) -> Result<()> {
+    confirm_path(Path::new(DATA_DIR))?;
+    fs::write(Path::new(DATA_DIR).join(name), contents)?;
     Ok(())
 }
+
+/// Reads `name` from the configuration directory, `None` if it hasn't been
+/// written yet.
+pub fn read_config<T: DeserializeOwned>(name: &str) -> Result<Option<T>> {
+    read(&config_path(name)?)
+}
+
+pub fn write_config<T: Serialize>(name: &str, value: &T) -> Result<()> {
+    write(&config_path(name)?, value)
+}
@@ -0,0 +1,81 @@
+use imgui::{Context, FontConfig, FontGlyphRanges, FontSource};
+use imgui_glium_renderer::Renderer;
+
+/// DejaVu Sans, which covers Latin, Greek and Cyrillic names as well as
+/// currency signs such as `€`.
+const BUNDLED: &[u8] = include_bytes!("../../resources/fonts/DejaVuSans.ttf");
+
+/// Pairs of first and last code points, ending in zero.
+static BUNDLED_RANGES: [u32; 17] = [
+    0x0020, 0x024F, // Basic Latin, Latin-1 and Latin Extended
+    0x0370, 0x03FF, // Greek
+    0x0400, 0x052F, // Cyrillic
+    0x1E00, 0x1EFF, // Latin Extended Additional, e.g. Vietnamese
+    0x2000, 0x206F, // General Punctuation
+    0x20A0, 0x20CF, // Currency Symbols
+    0x2100, 0x214F, // Letterlike Symbols
+    0x2190, 0x21FF, // Arrows
+    0,
+];
+
+/// What a user picked font is asked for, mainly Chinese, Japanese and Korean.
+static EXTRA_RANGES: [u32; 15] = [
+    0x3000, 0x30FF, // CJK Punctuation, Hiragana and Katakana
+    0x3130, 0x318F, // Hangul Compatibility Jamo
+    0x31F0, 0x31FF, // Katakana Phonetic Extensions
+    0x4E00, 0x9FFF, // CJK Unified Ideographs
+    0xAC00, 0xD7A3, // Hangul Syllables
+    0xFF00, 0xFFEF, // Half and full width forms
+    0x0370, 0x052F, // Greek and Cyrillic, in case the bundled font lacks some
+    0,
+];
+
+/// Whether `data` starts like a TrueType or OpenType font or collection.
+/// imgui aborts on anything else.
+pub fn is_font(data: &[u8]) -> bool {
+    matches!(
+        data.get(..4),
+        Some([0, 1, 0, 0] | b"OTTO" | b"true" | b"ttcf")
+    )
+}
+
+/// Replaces the fonts with the bundled one at `size` pixels, with glyphs
+/// missing from it taken from `extra`, e.g. a CJK font the user picked.
+/// The renderer has to reload the font texture afterwards.
+pub fn load(imgui: &mut Context, size: f32, extra: Option<&[u8]>) {
+    let fonts = imgui.fonts();
+    fonts.clear();
+
+    let mut sources = vec![FontSource::TtfData {
+        data: BUNDLED,
+        size_pixels: size,
+        config: Some(FontConfig {
+            glyph_ranges: FontGlyphRanges::from_slice(&BUNDLED_RANGES),
+            ..FontConfig::default()
+        }),
+    }];
+    if let Some(data) = extra {
+        sources.push(FontSource::TtfData {
+            data,
+            size_pixels: size,
+            config: Some(FontConfig {
+                glyph_ranges: FontGlyphRanges::from_slice(&EXTRA_RANGES),
+                ..FontConfig::default()
+            }),
+        });
+    }
+
+    fonts.add_font(&sources);
+}
+
+/// Loads the fonts and uploads the new atlas, for use between frames.
+pub fn reload(
+    imgui: &mut Context,
+    renderer: &mut Renderer,
+    size: f32,
+    extra: Option<&[u8]>,
+) -> anyhow::Result<()> {
+    load(imgui, size, extra);
+    renderer.reload_font_texture(imgui)?;
+    Ok(())
+}
@@ -0,0 +1,188 @@
+use super::{fonts, Changes, Chord, Textures};
+use imgui::internal::RawWrapper;
+use imgui::{sys, Context, DrawCmd, DrawData, Key, Ui};
+use std::collections::VecDeque;
+use std::ffi::{CStr, CString};
+use std::fmt::Write;
+use std::sync::{Mutex, MutexGuard};
+use std::time::Duration;
+
+/// imgui keeps its context in a global, so tests running in parallel take
+/// turns.
+static CONTEXT: Mutex<()> = Mutex::new(());
+
+/// Between every two frames, so that animations and double clicks play out
+/// the same on every run
+const FRAME_TIME: Duration = Duration::from_micros(16_667);
+
+enum Input {
+    /// Presses the widget
+    Press(sys::ImGuiID),
+    /// Starts editing the text input
+    Edit(sys::ImGuiID),
+    Text(String),
+    Key(Key, bool),
+}
+
+/// The imgui context of `System` without a window or renderer, for tests
+/// and benchmarks. Widgets are pressed and typed into by their id rather
+/// than by where they are, and frames produce a summary of their draw lists
+/// to compare with a snapshot.
+pub struct Headless {
+    pub imgui: Context,
+    /// Input for the coming frames, one batch per frame
+    input: VecDeque<Vec<Input>>,
+    _context: MutexGuard<'static, ()>,
+}
+
+/// The id imgui gives the widget at `path`, starting with the window, e.g.
+/// `["###add_participant", "##add_participant"]`. Tree nodes and pushed ids
+/// are steps of their own.
+pub fn id(path: &[&str]) -> sys::ImGuiID {
+    path.iter().fold(0, |seed, label| {
+        let label = CString::new(*label).expect("Labels have no nul bytes");
+        unsafe { sys::igImHashStr(label.as_ptr(), 0, seed) }
+    })
+}
+
+impl Headless {
+    /// A context with a display of `size` logical pixels and the bundled
+    /// font, styled like imgui's defaults.
+    pub fn new(size: [f32; 2]) -> Self {
+        let guard = CONTEXT.lock().unwrap_or_else(|e| e.into_inner());
+
+        let mut imgui = Context::create();
+        imgui.set_ini_filename(None);
+        imgui.io_mut().config_flags |= imgui::ConfigFlags::DOCKING_ENABLE;
+        imgui.io_mut().config_input_text_cursor_blink = false;
+        imgui.io_mut().display_size = size;
+        fonts::load(&mut imgui, 13.0, None);
+        imgui.fonts().build_rgba32_texture();
+
+        Self {
+            imgui,
+            input: VecDeque::new(),
+            _context: guard,
+        }
+    }
+
+    /// The batch of input for the `n`th frame from now.
+    fn batch(&mut self, n: usize) -> &mut Vec<Input> {
+        while self.input.len() <= n {
+            self.input.push_back(Vec::new());
+        }
+        &mut self.input[n]
+    }
+
+    /// Presses the widget at `path`, see `id`, on the next frame.
+    pub fn click(&mut self, path: &[&str]) {
+        self.batch(0).push(Input::Press(id(path)));
+    }
+
+    /// Starts editing the text input at `path` on the next frame and types
+    /// `text` on the one after.
+    pub fn type_into(&mut self, path: &[&str], text: &str) {
+        self.batch(0).push(Input::Edit(id(path)));
+        self.batch(1).push(Input::Text(text.to_string()));
+    }
+
+    /// Presses `key` on the next frame and releases it on the one after.
+    pub fn press(&mut self, key: Key) {
+        self.batch(0).push(Input::Key(key, true));
+        self.batch(1).push(Input::Key(key, false));
+    }
+
+    /// Whether input is still waiting for frames.
+    pub fn busy(&self) -> bool {
+        !self.input.is_empty()
+    }
+
+    /// Builds a frame with `run_ui` and summarises what it draws. Changes
+    /// to the context are dropped, as there is no renderer to apply them
+    /// with.
+    pub fn frame<F: FnOnce(&mut Ui, &mut Textures, &mut Changes, &[Chord])>(
+        &mut self,
+        run_ui: F,
+    ) -> String {
+        for input in self.input.pop_front().unwrap_or_default() {
+            match input {
+                Input::Press(id) => unsafe { sys::igActivateItem(id) },
+                Input::Edit(id) => unsafe {
+                    sys::igActivateItem(id);
+                    (*sys::igGetCurrentContext()).NavNextActivateFlags =
+                        sys::ImGuiActivateFlags_PreferInput as i32;
+                },
+                Input::Text(text) => text
+                    .chars()
+                    .for_each(|c| self.imgui.io_mut().add_input_character(c)),
+                Input::Key(key, down) => self.imgui.io_mut().add_key_event(key, down),
+            }
+        }
+        self.imgui.io_mut().update_delta_time(FRAME_TIME);
+
+        let ui = self.imgui.new_frame();
+        let mut textures = Textures { backend: None };
+        run_ui(ui, &mut textures, &mut Changes::default(), &[]);
+        summary(self.imgui.render())
+    }
+
+    /// Runs frames until the queued input is used up and one more to show
+    /// its results, returning the summary of the last.
+    pub fn settle<F: FnMut(&mut Ui, &mut Textures, &mut Changes, &[Chord])>(
+        &mut self,
+        mut run_ui: F,
+    ) -> String {
+        while self.busy() {
+            self.frame(&mut run_ui);
+        }
+        self.frame(run_ui)
+    }
+}
+
+/// One line per draw list with its owner, size and a checksum of its
+/// vertices, followed by its draw commands.
+fn summary(draw_data: &DrawData) -> String {
+    let mut s = String::new();
+    for list in draw_data.draw_lists() {
+        let owner = unsafe { list.raw()._OwnerName };
+        let owner = if owner.is_null() {
+            "?".into()
+        } else {
+            unsafe { CStr::from_ptr(owner) }.to_string_lossy()
+        };
+
+        // FNV-1a over positions to a hundredth of a pixel, texture
+        // coordinates and colours
+        let mut checksum: u64 = 0xcbf2_9ce4_8422_2325;
+        for vertex in list.vtx_buffer() {
+            let values = [
+                (vertex.pos[0] * 100.).round() as i32 as u32,
+                (vertex.pos[1] * 100.).round() as i32 as u32,
+                vertex.uv[0].to_bits(),
+                vertex.uv[1].to_bits(),
+                u32::from_le_bytes(vertex.col),
+            ];
+            for byte in values.iter().flat_map(|v| v.to_le_bytes()) {
+                checksum = (checksum ^ u64::from(byte)).wrapping_mul(0x100_0000_01b3);
+            }
+        }
+
+        let _ = writeln!(
+            s,
+            "{owner}: {} vertices, {} indices, checksum {checksum:016x}",
+            list.vtx_buffer().len(),
+            list.idx_buffer().len()
+        );
+        for command in list.commands() {
+            if let DrawCmd::Elements { count, cmd_params } = command {
+                let [x0, y0, x1, y1] = cmd_params.clip_rect;
+                let _ = writeln!(
+                    s,
+                    "    {count} indices clipped to [{x0}, {y0}, {x1}, {y1}], texture {}",
+                    cmd_params.texture_id.id()
+                );
+            }
+        }
+    }
+    s
+}
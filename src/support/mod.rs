@@ -3,14 +3,24 @@ use glium::glutin::event::{Event, WindowEvent};
 use glium::glutin::event_loop::{ControlFlow, EventLoop};
 use glium::glutin::window::WindowBuilder;
 use glium::{Display, Surface};
-use imgui::{Context, FontConfig, FontGlyphRanges, FontSource, Ui};
+use imgui::{Context, StyleColor, Ui};
 use imgui_glium_renderer::Renderer;
 use imgui_winit_support::{HiDpiMode, WinitPlatform};
 use std::path::Path;
-use std::time::Instant;
+use std::time::{Duration, Instant};
 
 mod clipboard;
-mod file_system;
+pub mod file_system;
+pub mod fonts;
+// Benchmarks only draw frames, tests also press and type
+#[cfg_attr(not(test), allow(dead_code))]
+pub mod headless;
+mod shortcuts;
+mod textures;
+
+pub use shortcuts::Chord;
+use shortcuts::Shortcuts;
+pub use textures::Textures;
 
 pub struct System {
     pub event_loop: EventLoop<()>,
@@ -18,10 +28,36 @@ pub struct System {
     pub imgui: Context,
     pub platform: WinitPlatform,
     pub renderer: Renderer,
-    pub font_size: f32,
 }
 
-pub fn init(title: &str) -> System {
+type Change = Box<dyn FnOnce(&mut Context, &mut Renderer)>;
+
+/// How long frames keep being drawn after the last input, so that imgui can
+/// finish fading in popups and reacting to hovering.
+const GRACE: Duration = Duration::from_millis(500);
+
+/// Changes to the imgui context, such as to its style or fonts, that cannot
+/// be made while a frame is being built. They are applied after it.
+#[derive(Default)]
+pub struct Changes {
+    changes: Vec<Change>,
+    /// When a frame is needed even if there is no input
+    wake: Option<Instant>,
+}
+
+impl Changes {
+    pub fn push(&mut self, change: impl FnOnce(&mut Context, &mut Renderer) + 'static) {
+        self.changes.push(Box::new(change));
+    }
+
+    /// Asks for a frame at `at` at the latest, e.g. to finish a delayed save
+    /// while the user is away.
+    pub fn wake_at(&mut self, at: Instant) {
+        self.wake = Some(self.wake.map_or(at, |wake| wake.min(at)));
+    }
+}
+
+pub fn init(title: &str, size: [f64; 2]) -> System {
     let title = match Path::new(&title).file_name() {
         Some(file_name) => file_name.to_str().unwrap(),
         None => title,
@@ -30,13 +66,23 @@ pub fn init(title: &str) -> System {
     let context = glutin::ContextBuilder::new().with_vsync(true);
     let builder = WindowBuilder::new()
         .with_title(title.to_owned())
-        .with_resizable(false)
-        .with_inner_size(glutin::dpi::LogicalSize::new(800f64, 500f64));
+        .with_resizable(true)
+        .with_inner_size(glutin::dpi::LogicalSize::new(size[0], size[1]));
     let display =
         Display::new(builder, context, &event_loop).expect("Failed to initialize display");
 
     let mut imgui = Context::create();
-    imgui.set_ini_filename(None);
+    // Keeps the window layout, including docking, between runs
+    match file_system::config_path("imgui.ini") {
+        Ok(path) => imgui.set_ini_filename(path),
+        Err(e) => {
+            eprintln!("Failed to find a place for the layout: {e}");
+            imgui.set_ini_filename(None);
+        }
+    }
+    imgui.io_mut().config_flags |= imgui::ConfigFlags::DOCKING_ENABLE;
+    // A blinking cursor would need frames while nothing else happens
+    imgui.io_mut().config_input_text_cursor_blink = false;
 
     if let Some(backend) = clipboard::init() {
         imgui.set_clipboard_backend(backend);
@@ -69,6 +115,8 @@ pub fn init(title: &str) -> System {
     // value (as the scaling is handled by winit)
     let font_size = 13.0;
 
+    fonts::load(&mut imgui, font_size, None);
+
     let renderer = Renderer::init(&mut imgui, &display).expect("Failed to initialize renderer");
 
     System {
@@ -77,12 +125,16 @@ pub fn init(title: &str) -> System {
         imgui,
         platform,
         renderer,
-        font_size,
     }
 }
 
 impl System {
-    pub fn main_loop<F: FnMut(&mut bool, &mut Ui) + 'static>(self, mut run_ui: F) {
+    pub fn main_loop<
+        F: FnMut(&mut bool, &mut Ui, &mut Textures, &mut Changes, &[Chord]) + 'static,
+    >(
+        self,
+        mut run_ui: F,
+    ) {
         let System {
             event_loop,
             display,
@@ -92,44 +144,95 @@ impl System {
             ..
         } = self;
         let mut last_frame = Instant::now();
+        let mut changes = Changes::default();
+        let mut shortcuts = Shortcuts::default();
+        // Frames are only drawn while something may change on screen, and
+        // otherwise the loop sleeps until the next event
+        let mut busy_until = Instant::now() + GRACE;
 
         event_loop.run(move |event, _, control_flow| match event {
-            Event::NewEvents(_) => {
-                let now = Instant::now();
-                imgui.io_mut().update_delta_time(now - last_frame);
-                last_frame = now;
-            }
             Event::MainEventsCleared => {
-                let gl_window = display.gl_window();
-                platform
-                    .prepare_frame(imgui.io_mut(), gl_window.window())
-                    .expect("Failed to prepare frame");
-                gl_window.window().request_redraw();
+                let now = Instant::now();
+                let woken = changes.wake.is_some_and(|wake| wake <= now);
+                if now < busy_until || woken {
+                    if woken {
+                        changes.wake = None;
+                    }
+                    imgui.io_mut().update_delta_time(now - last_frame);
+                    last_frame = now;
+
+                    let gl_window = display.gl_window();
+                    platform
+                        .prepare_frame(imgui.io_mut(), gl_window.window())
+                        .expect("Failed to prepare frame");
+                    gl_window.window().request_redraw();
+                }
+
+                if !matches!(control_flow, ControlFlow::ExitWithCode(_)) {
+                    *control_flow = if now < busy_until {
+                        ControlFlow::Poll
+                    } else if let Some(wake) = changes.wake {
+                        ControlFlow::WaitUntil(wake)
+                    } else {
+                        ControlFlow::Wait
+                    };
+                }
             }
             Event::RedrawRequested(_) => {
                 let ui = imgui.frame();
 
                 let mut run = true;
-                run_ui(&mut run, ui);
+                let mut textures = Textures {
+                    backend: Some((&display, renderer.textures())),
+                };
+                let chords = shortcuts.take();
+                run_ui(&mut run, ui, &mut textures, &mut changes, &chords);
                 if !run {
                     *control_flow = ControlFlow::Exit;
                 }
 
+                // Whatever the panels leave uncovered matches the style
+                let [r, g, b, a] = ui.style_color(StyleColor::DockingEmptyBg);
                 let gl_window = display.gl_window();
                 let mut target = display.draw();
-                target.clear_color_srgb(1.0, 1.0, 1.0, 1.0);
+                target.clear_color_srgb(r, g, b, a);
                 platform.prepare_render(ui, gl_window.window());
                 let draw_data = imgui.render();
                 renderer
                     .render(&mut target, draw_data)
                     .expect("Rendering failed");
                 target.finish().expect("Failed to swap buffers");
+
+                if !changes.changes.is_empty() {
+                    // Shown on the next frames
+                    busy_until = busy_until.max(Instant::now() + GRACE);
+                }
+                for change in changes.changes.drain(..) {
+                    change(&mut imgui, &mut renderer);
+                }
             }
             Event::WindowEvent {
                 event: WindowEvent::CloseRequested,
                 ..
             } => *control_flow = ControlFlow::Exit,
             event => {
+                match &event {
+                    Event::WindowEvent {
+                        event: WindowEvent::ModifiersChanged(modifiers),
+                        ..
+                    } => shortcuts.modifiers_changed(*modifiers),
+                    Event::WindowEvent {
+                        event: WindowEvent::KeyboardInput { input, .. },
+                        ..
+                    } => shortcuts.key(input),
+                    _ => {}
+                }
+                // Raw device events arrive even when the window is in the
+                // background, only those of the window count as input
+                if let Event::WindowEvent { .. } = event {
+                    busy_until = Instant::now() + GRACE;
+                }
+
                 let gl_window = display.gl_window();
                 platform.handle_event(imgui.io_mut(), gl_window.window(), &event);
             }
@@ -0,0 +1,63 @@
+use glium::glutin::event::{ElementState, KeyboardInput, ModifiersState, VirtualKeyCode};
+
+/// A key pressed while holding Ctrl, e.g. Ctrl+Shift+N.
+#[derive(Clone, Copy, PartialEq, Eq, Debug)]
+pub struct Chord {
+    pub shift: bool,
+    pub key: VirtualKeyCode,
+}
+
+impl Chord {
+    pub const fn ctrl(key: VirtualKeyCode) -> Self {
+        Self { shift: false, key }
+    }
+
+    pub const fn ctrl_shift(key: VirtualKeyCode) -> Self {
+        Self { shift: true, key }
+    }
+
+    /// How the chord is written next to the action it runs.
+    pub fn label(&self) -> String {
+        let key = match self.key {
+            VirtualKeyCode::Comma => ",".to_string(),
+            VirtualKeyCode::Period => ".".to_string(),
+            key => format!("{key:?}"),
+        };
+        if self.shift {
+            format!("Ctrl+Shift+{key}")
+        } else {
+            format!("Ctrl+{key}")
+        }
+    }
+}
+
+/// Collects the chords pressed between frames. Plain key presses are left
+/// to imgui alone so that typing is never taken for a shortcut.
+#[derive(Default)]
+pub struct Shortcuts {
+    modifiers: ModifiersState,
+    pressed: Vec<Chord>,
+}
+
+impl Shortcuts {
+    pub fn modifiers_changed(&mut self, modifiers: ModifiersState) {
+        self.modifiers = modifiers;
+    }
+
+    pub fn key(&mut self, input: &KeyboardInput) {
+        if input.state != ElementState::Pressed || !self.modifiers.ctrl() {
+            return;
+        }
+        if let Some(key) = input.virtual_keycode {
+            self.pressed.push(Chord {
+                shift: self.modifiers.shift(),
+                key,
+            });
+        }
+    }
+
+    /// The chords pressed since the last frame.
+    pub fn take(&mut self) -> Vec<Chord> {
+        std::mem::take(&mut self.pressed)
+    }
+}
@@ -0,0 +1,48 @@
+use anyhow::{anyhow, Result};
+use glium::texture::{ClientFormat, MipmapsOption, RawImage2d};
+use glium::uniforms::{MagnifySamplerFilter, MinifySamplerFilter, SamplerBehavior};
+use glium::{Display, Texture2d};
+use imgui::TextureId;
+use imgui_glium_renderer::Texture;
+use std::borrow::Cow;
+use std::rc::Rc;
+
+/// Access to the renderer's texture table from inside the UI closure.
+pub struct Textures<'a> {
+    /// The display and the renderer's textures, or `None` when running
+    /// headless with nowhere to upload to
+    pub(super) backend: Option<(&'a Display, &'a mut imgui::Textures<Texture>)>,
+}
+
+impl Textures<'_> {
+    /// Uploads an RGBA image, sampled without smoothing so that pixel art
+    /// such as QR codes stays crisp when scaled up.
+    pub fn upload_rgba(&mut self, width: u32, height: u32, data: Vec<u8>) -> Result<TextureId> {
+        let (display, textures) = self
+            .backend
+            .as_mut()
+            .ok_or_else(|| anyhow!("No display to show images on"))?;
+        let image = RawImage2d {
+            data: Cow::Owned(data),
+            width,
+            height,
+            format: ClientFormat::U8U8U8U8,
+        };
+        let texture = Texture2d::with_mipmaps(*display, image, MipmapsOption::NoMipmap)?;
+
+        Ok(textures.insert(Texture {
+            texture: Rc::new(texture),
+            sampler: SamplerBehavior {
+                minify_filter: MinifySamplerFilter::Nearest,
+                magnify_filter: MagnifySamplerFilter::Nearest,
+                ..Default::default()
+            },
+        }))
+    }
+
+    pub fn remove(&mut self, id: TextureId) {
+        if let Some((_, textures)) = &mut self.backend {
+            textures.remove(id);
+        }
+    }
+}
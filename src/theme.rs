@@ -0,0 +1,227 @@
+use imgui::StyleColor;
+use serde::{Deserialize, Serialize};
+
+/// The built-in colour schemes, which custom themes start from.
+#[derive(Clone, Copy, PartialEq, Eq, Debug, Serialize, Deserialize)]
+pub enum Preset {
+    Dark,
+    Light,
+    Classic,
+    HighContrast,
+}
+
+impl Preset {
+    pub const ALL: [Preset; 4] = [
+        Preset::Dark,
+        Preset::Light,
+        Preset::Classic,
+        Preset::HighContrast,
+    ];
+
+    /// How the preset is referred to in the settings file.
+    pub fn key(&self) -> &'static str {
+        match self {
+            Preset::Dark => "Dark",
+            Preset::Light => "Light",
+            Preset::Classic => "Classic",
+            Preset::HighContrast => "HighContrast",
+        }
+    }
+
+    /// Id of the preset's name in the catalogues.
+    pub fn name(&self) -> &'static str {
+        match self {
+            Preset::Dark => "theme-dark",
+            Preset::Light => "theme-light",
+            Preset::Classic => "theme-classic",
+            Preset::HighContrast => "theme-high-contrast",
+        }
+    }
+
+    fn apply(&self, style: &mut imgui::Style) {
+        match self {
+            Preset::Dark => {
+                style.use_dark_colors();
+            }
+            Preset::Light => {
+                style.use_light_colors();
+            }
+            Preset::Classic => {
+                style.use_classic_colors();
+            }
+            Preset::HighContrast => high_contrast(style),
+        }
+    }
+}
+
+/// White on black with yellow for whatever can be interacted with, and
+/// borders around every frame.
+fn high_contrast(style: &mut imgui::Style) {
+    const BLACK: [f32; 4] = [0., 0., 0., 1.];
+    const WHITE: [f32; 4] = [1., 1., 1., 1.];
+    const GREY: [f32; 4] = [0.25, 0.25, 0.25, 1.];
+    const YELLOW: [f32; 4] = [1., 0.9, 0., 1.];
+    const DARK_YELLOW: [f32; 4] = [0.45, 0.4, 0., 1.];
+
+    style.use_dark_colors();
+    style.window_border_size = 1.;
+    style.frame_border_size = 1.;
+    style.popup_border_size = 1.;
+
+    for color in [
+        StyleColor::WindowBg,
+        StyleColor::ChildBg,
+        StyleColor::PopupBg,
+        StyleColor::MenuBarBg,
+        StyleColor::TitleBg,
+        StyleColor::TitleBgCollapsed,
+        StyleColor::ScrollbarBg,
+        StyleColor::FrameBg,
+        StyleColor::Button,
+        StyleColor::Tab,
+        StyleColor::TabUnfocused,
+        StyleColor::TableRowBg,
+        StyleColor::DockingEmptyBg,
+    ] {
+        style[color] = BLACK;
+    }
+    for color in [
+        StyleColor::Text,
+        StyleColor::Border,
+        StyleColor::Separator,
+        StyleColor::TableBorderStrong,
+    ] {
+        style[color] = WHITE;
+    }
+    for color in [
+        StyleColor::TitleBgActive,
+        StyleColor::FrameBgHovered,
+        StyleColor::ButtonHovered,
+        StyleColor::Header,
+        StyleColor::TabUnfocusedActive,
+        StyleColor::TableRowBgAlt,
+        StyleColor::TableBorderLight,
+        StyleColor::ScrollbarGrab,
+    ] {
+        style[color] = GREY;
+    }
+    for color in [
+        StyleColor::FrameBgActive,
+        StyleColor::ButtonActive,
+        StyleColor::HeaderHovered,
+        StyleColor::TabHovered,
+        StyleColor::TabActive,
+        StyleColor::TextSelectedBg,
+        StyleColor::ResizeGrip,
+    ] {
+        style[color] = DARK_YELLOW;
+    }
+    for color in [
+        StyleColor::CheckMark,
+        StyleColor::SliderGrab,
+        StyleColor::SliderGrabActive,
+        StyleColor::HeaderActive,
+        StyleColor::ScrollbarGrabHovered,
+        StyleColor::ScrollbarGrabActive,
+        StyleColor::SeparatorHovered,
+        StyleColor::SeparatorActive,
+        StyleColor::ResizeGripHovered,
+        StyleColor::ResizeGripActive,
+        StyleColor::NavHighlight,
+        StyleColor::DockingPreview,
+    ] {
+        style[color] = YELLOW;
+    }
+    style[StyleColor::TextDisabled] = [0.8, 0.8, 0.8, 1.];
+}
+
+/// Colours that carry a meaning, used for the same thing in every window.
+#[derive(Clone, Copy, PartialEq, Debug, Serialize, Deserialize)]
+pub struct Palette {
+    /// Money someone still has to pay
+    pub debt: [f32; 4],
+    /// Money someone is still owed
+    pub credit: [f32; 4],
+    /// Items left out of a receipt
+    pub exclusion: [f32; 4],
+    /// Something that looks off but does not stop anything
+    pub warning: [f32; 4],
+    pub error: [f32; 4],
+}
+
+impl Palette {
+    /// Colours readable on the preset's background.
+    pub fn of(preset: Preset) -> Self {
+        match preset {
+            Preset::Dark | Preset::Classic => Self {
+                debt: [1., 0.45, 0.45, 1.],
+                credit: [0.4, 0.85, 0.45, 1.],
+                exclusion: [0.8, 0.6, 1., 1.],
+                warning: [1., 0.7, 0.2, 1.],
+                error: [1., 0.3, 0.3, 1.],
+            },
+            Preset::Light => Self {
+                debt: [0.75, 0.1, 0.1, 1.],
+                credit: [0.05, 0.5, 0.15, 1.],
+                exclusion: [0.5, 0.2, 0.7, 1.],
+                warning: [0.7, 0.4, 0., 1.],
+                error: [0.8, 0., 0., 1.],
+            },
+            Preset::HighContrast => Self {
+                debt: [1., 0.4, 0.4, 1.],
+                credit: [0.3, 1., 0.3, 1.],
+                exclusion: [1., 0.5, 1., 1.],
+                warning: [1., 1., 0., 1.],
+                error: [1., 0.3, 0.3, 1.],
+            },
+        }
+    }
+
+    /// The colour of a balance of `cents`, none when it is settled.
+    pub fn standing(&self, cents: i64) -> Option<[f32; 4]> {
+        match cents {
+            c if c < 0 => Some(self.debt),
+            c if c > 0 => Some(self.credit),
+            _ => None,
+        }
+    }
+}
+
+/// A preset with its own palette and background, either one of the built-in
+/// themes or one the user saved.
+#[derive(Clone, PartialEq, Debug, Serialize, Deserialize)]
+pub struct Theme {
+    pub name: String,
+    pub preset: Preset,
+    pub palette: Palette,
+    /// Behind the panels, where nothing is docked
+    pub background: [f32; 4],
+}
+
+impl Theme {
+    pub fn built_in(preset: Preset) -> Self {
+        let background = match preset {
+            Preset::Dark => [0.1, 0.1, 0.1, 1.],
+            Preset::Light => [0.86, 0.86, 0.86, 1.],
+            Preset::Classic => [0.12, 0.12, 0.16, 1.],
+            Preset::HighContrast => [0., 0., 0., 1.],
+        };
+        Self {
+            name: preset.key().to_string(),
+            preset,
+            palette: Palette::of(preset),
+            background,
+        }
+    }
+
+    /// Whether `name` is taken by a built-in theme.
+    pub fn is_built_in(name: &str) -> bool {
+        Preset::ALL.iter().any(|p| p.key() == name)
+    }
+
+    pub fn apply(&self, style: &mut imgui::Style) {
+        self.preset.apply(style);
+        // Also what the OS window is cleared to
+        style[StyleColor::DockingEmptyBg] = self.background;
+    }
+}
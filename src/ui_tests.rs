@@ -0,0 +1,189 @@
+use crate::locale::{Language, Locale};
+use crate::settings::Settings;
+use crate::support::headless::Headless;
+use crate::{Participant, Receipt, State};
+use imgui::Key;
+use std::fs;
+use std::path::Path;
+
+const SIZE: [f32; 2] = [800., 500.];
+
+/// The whole interface in English, at the size the default layout was drawn
+/// for so that nothing asks for the window size to be saved.
+struct App {
+    headless: Headless,
+    state: State,
+}
+
+impl App {
+    fn new() -> Self {
+        let settings = Settings {
+            language: Language::English,
+            window_size: [f64::from(SIZE[0]), f64::from(SIZE[1])],
+            ..Settings::default()
+        };
+        let mut headless = Headless::new(SIZE);
+        let base_style = *headless.imgui.style();
+        *headless.imgui.style_mut() = settings.style(&base_style);
+
+        App {
+            headless,
+            state: State {
+                locale: Locale::new(settings.language),
+                settings,
+                ..Default::default()
+            },
+        }
+    }
+
+    fn frame(&mut self) -> String {
+        let state = &mut self.state;
+        self.headless.frame(|ui, textures, changes, chords| {
+            crate::frame(ui, state, textures, changes, chords)
+        })
+    }
+
+    fn settle(&mut self) -> String {
+        let state = &mut self.state;
+        self.headless.settle(|ui, textures, changes, chords| {
+            crate::frame(ui, state, textures, changes, chords)
+        })
+    }
+
+    fn names(&self) -> Vec<&str> {
+        self.state
+            .participants
+            .iter()
+            .map(|p| p.name.as_str())
+            .collect()
+    }
+}
+
+/// Compares `summary` with the snapshot `name`, writing it instead if there
+/// is none yet or `UPDATE_SNAPSHOTS` is set.
+fn assert_snapshot(name: &str, summary: &str) {
+    let path = Path::new(env!("CARGO_MANIFEST_DIR"))
+        .join("src/snapshots")
+        .join(format!("{name}.snap"));
+    if std::env::var_os("UPDATE_SNAPSHOTS").is_some() || !path.exists() {
+        fs::create_dir_all(path.parent().unwrap()).unwrap();
+        fs::write(&path, summary).unwrap();
+        return;
+    }
+
+    let snapshot = fs::read_to_string(&path).unwrap();
+    let difference = snapshot
+        .lines()
+        .chain(["<end>"])
+        .zip(summary.lines().chain(["<end>"]))
+        .enumerate()
+        .find(|(_, (expected, actual))| expected != actual);
+    if let Some((n, (expected, actual))) = difference {
+        panic!(
+            "{} differs on line {}, run with UPDATE_SNAPSHOTS=1 if that is intended\n\
+             expected: {expected}\n  actual: {actual}",
+            path.display(),
+            n + 1
+        );
+    }
+}
+
+#[test]
+fn main_windows_match_snapshot() {
+    let mut app = App::new();
+    // Windows measure themselves on their first frame
+    app.frame();
+    assert_snapshot("main_windows", &app.frame());
+}
+
+#[test]
+fn enter_adds_typed_participant() {
+    let mut app = App::new();
+    app.frame();
+
+    app.headless
+        .type_into(&["###add_participant", "##add_participant"], "Alice");
+    app.settle();
+    assert_eq!(app.state.tmp_name, "Alice");
+    assert!(app.names().is_empty());
+
+    app.headless.press(Key::Enter);
+    let summary = app.settle();
+    assert_eq!(app.names(), ["Alice"]);
+    assert!(app.state.tmp_name.is_empty());
+    assert_snapshot("participant_added", &summary);
+}
+
+#[test]
+fn add_button_adds_typed_participant() {
+    let mut app = App::new();
+    app.frame();
+
+    app.headless
+        .type_into(&["###add_participant", "##add_participant"], "Bob");
+    app.settle();
+    let add = app.state.locale.tr("add");
+    app.headless.click(&["###add_participant", &add]);
+    app.settle();
+    assert_eq!(app.names(), ["Bob"]);
+}
+
+#[test]
+fn enter_adds_typed_receipt() {
+    let mut app = App::new();
+    app.frame();
+
+    app.headless
+        .type_into(&["###add_receipt", "##receipt_label"], "Groceries");
+    app.settle();
+    app.headless
+        .type_into(&["###add_receipt", "##receipt_total"], "12.5");
+    app.settle();
+    app.headless.press(Key::Enter);
+    app.settle();
+
+    let receipts: Vec<(&str, f32)> = app
+        .state
+        .receipts
+        .iter()
+        .map(|r| (r.label.as_str(), r.total))
+        .collect();
+    assert_eq!(receipts, [("Groceries", 12.5)]);
+}
+
+#[test]
+fn leaving_a_receipt_moves_its_cost() {
+    let mut app = App::new();
+    app.state.settings.auto_share = true;
+    app.state
+        .add_participant(Participant::new("Alice".to_string()));
+    app.state
+        .add_participant(Participant::new("Bob".to_string()));
+    app.state
+        .add_receipt(Receipt::new("Groceries".to_string(), 10., false));
+    let alice = app.state.participants[0].id;
+    app.frame();
+
+    let row = format!("##row_{alice}");
+    let manage = format!(
+        "{}##manage_receipts_0",
+        app.state.locale.tr("manage-receipts")
+    );
+    let receipt = format!("Groceries ({})##0", app.state.locale.tr("partake"));
+    app.headless.click(&["###participants", &row]);
+    app.settle();
+    app.headless.click(&["###participants", &row, &manage]);
+    app.settle();
+    app.headless
+        .click(&["###participants", &row, &manage, &receipt]);
+    let summary = app.settle();
+
+    let shares: Vec<f32> = app
+        .state
+        .participants
+        .iter()
+        .map(|p| p.share(&app.state))
+        .collect();
+    assert_eq!(shares, [0., 10.]);
+    assert_snapshot("receipt_left", &summary);
+}
@@ -0,0 +1,385 @@
+use crate::command::Action;
+use crate::locale::Message;
+use crate::support::file_system;
+use crate::{file_format, payment, Participant, Session, State};
+use serde::{Deserialize, Serialize};
+use std::collections::HashMap;
+use uuid::Uuid;
+
+const WORKSPACE_FILE: &str = "workspace.json";
+/// Where a workspace file that could not be read is kept when it is saved
+/// over
+const BACKUP_FILE: &str = "workspace.json.bak";
+
+/// Every session the user keeps, plus the people they split costs with.
+///
+/// The active session is edited in place on `State`; its entry in
+/// `sessions` is only brought up to date by `State::store_session`.
+#[derive(Clone, Debug, Serialize, Deserialize)]
+pub struct Workspace {
+    pub sessions: Vec<Session>,
+    pub active: usize,
+    /// Participants are added to sessions from here with the same id, which
+    /// is what ties a person's balances together across sessions
+    pub contacts: Vec<Participant>,
+}
+
+impl Default for Workspace {
+    fn default() -> Self {
+        Self {
+            sessions: vec![Session::new("Session 1".to_string())],
+            active: 0,
+            contacts: Vec::new(),
+        }
+    }
+}
+
+impl Workspace {
+    /// Adds or updates the contact sharing `participant`'s id.
+    pub fn remember(&mut self, participant: &Participant) {
+        match self.contacts.iter_mut().find(|c| c.id == participant.id) {
+            Some(contact) => *contact = participant.clone(),
+            None => self.contacts.push(participant.clone()),
+        }
+    }
+}
+
+#[derive(Default)]
+pub struct WorkspaceUi {
+    pub totals_open: bool,
+    rename: String,
+    /// What went wrong saving the workspace
+    pub message: Option<String>,
+    /// Balances of every session but the live one, worked out when the
+    /// totals are first shown after a session is stored or opened
+    stored_balances: Option<Vec<HashMap<Uuid, i64>>>,
+}
+
+impl State {
+    /// Copies the live session back into the workspace.
+    pub fn store_session(&mut self) {
+        let session = self.session();
+        for participant in session.participants.iter() {
+            self.workspace.remember(participant);
+        }
+        self.workspace.sessions[self.workspace.active] = session;
+        self.workspace_ui.stored_balances = None;
+    }
+
+    /// Makes session `index` the live one, bringing its recurring expenses
+    /// up to date.
+    fn open_session(&mut self, index: usize) {
+        self.workspace.active = index;
+        self.workspace_ui.stored_balances = None;
+        self.load_session(self.workspace.sessions[index].clone());
+        self.recurring_ui.generated = self.generate_recurring(chrono::Local::now().date_naive());
+        self.settings.last_session = Some(self.session_name.clone());
+        self.settings_changed();
+    }
+
+    pub fn switch_session(&mut self, index: usize) {
+        self.store_session();
+        self.open_session(index);
+    }
+
+    pub fn new_session(&mut self) {
+        self.store_session();
+        let name = self.locale.tr_args(
+            "session-name",
+            &[("number", (self.workspace.sessions.len() + 1).into())],
+        );
+        self.workspace.sessions.push(Session::new(name));
+        self.switch_session(self.workspace.sessions.len() - 1);
+    }
+
+    pub fn remove_session(&mut self) {
+        if self.workspace.sessions.len() < 2 {
+            return;
+        }
+        self.workspace.sessions.remove(self.workspace.active);
+        self.open_session(self.workspace.active.saturating_sub(1));
+    }
+
+    /// Writes the workspace, see `files_to_save`. Returns a note for the
+    /// user if the file there had to be backed up.
+    pub fn save_workspace(&mut self) -> anyhow::Result<Option<Message>> {
+        self.store_session();
+        let existing = file_system::read_data(WORKSPACE_FILE)?;
+        let files = files_to_save(existing.as_deref(), &self.workspace)?;
+        for (name, contents) in files.iter() {
+            file_system::write_data(name, contents)?;
+        }
+        let backed_up = files.iter().any(|(name, _)| *name == BACKUP_FILE);
+        Ok(backed_up.then(|| Message::new("workspace-backed-up").arg("path", BACKUP_FILE)))
+    }
+
+    /// Restores the saved workspace, keeping the empty default if there is
+    /// none.
+    pub fn load_workspace(&mut self) -> anyhow::Result<()> {
+        if let Some(text) = file_system::read_data(WORKSPACE_FILE)? {
+            let workspace = file_format::from_json(&text)?;
+            if workspace.sessions.is_empty() {
+                return Ok(());
+            }
+            let active = workspace.active.min(workspace.sessions.len() - 1);
+            self.workspace = workspace;
+            self.open_session(active);
+        }
+        Ok(())
+    }
+}
+
+/// The files to write, in order, to save `workspace` over `existing`. A
+/// file from a newer version of the app is refused, as it would lose data.
+/// One that does not read as a workspace, e.g. because it failed to load
+/// and the default workspace took its place, is backed up first.
+fn files_to_save(
+    existing: Option<&str>,
+    workspace: &Workspace,
+) -> anyhow::Result<Vec<(&'static str, String)>> {
+    let mut files = Vec::new();
+    if let Some(existing) = existing {
+        file_format::check_writable(existing)?;
+        if file_format::from_json(existing).is_err() {
+            files.push((BACKUP_FILE, existing.to_string()));
+        }
+    }
+    files.push((WORKSPACE_FILE, file_format::to_json(workspace)?));
+    Ok(files)
+}
+
+/// Each person's balance in every session, in cents, keyed by participant id.
+pub fn totals(state: &mut State) -> HashMap<Uuid, Vec<i64>> {
+    let count = state.workspace.sessions.len();
+    let active = state.workspace.active;
+    let live = state.balances();
+    let sessions = &state.workspace.sessions;
+    let stored = state.workspace_ui.stored_balances.get_or_insert_with(|| {
+        sessions
+            .iter()
+            .enumerate()
+            .map(|(i, session)| {
+                if i == active {
+                    HashMap::new()
+                } else {
+                    payment::balances(&State::from(session.clone()))
+                }
+            })
+            .collect()
+    });
+
+    let mut totals: HashMap<Uuid, Vec<i64>> = HashMap::new();
+    for (i, balances) in stored.iter().enumerate() {
+        let balances = if i == active { live.as_ref() } else { balances };
+        for (id, balance) in balances {
+            totals.entry(*id).or_insert_with(|| vec![0; count])[i] = *balance;
+        }
+    }
+
+    totals
+}
+
+pub fn menu(ui: &imgui::Ui, state: &mut State) {
+    ui.menu(state.locale.tr("session-menu"), || {
+        let mut switch = None;
+        for (i, session) in state.workspace.sessions.iter().enumerate() {
+            let name = if i == state.workspace.active {
+                &state.session_name
+            } else {
+                &session.name
+            };
+            if ui
+                .menu_item_config(format!("{name}##session_{i}"))
+                .selected(i == state.workspace.active)
+                .build()
+            {
+                switch = Some(i);
+            }
+        }
+        if let Some(i) = switch.filter(|i| *i != state.workspace.active) {
+            state.switch_session(i);
+        }
+
+        ui.separator();
+        if ui.menu_item(state.locale.tr("new-session")) {
+            state.new_session();
+        }
+        ui.menu(state.locale.tr("rename-session"), || {
+            let workspace_ui = &mut state.workspace_ui;
+            ui.input_text("##rename_session", &mut workspace_ui.rename)
+                .hint(&state.session_name)
+                .build();
+            ui.same_line();
+            let _danger_token = ui.begin_disabled(workspace_ui.rename.trim().is_empty());
+            if ui.button(state.locale.tr("rename")) {
+                state.session_name = workspace_ui.rename.trim().to_string();
+                workspace_ui.rename.clear();
+            }
+        });
+        if ui
+            .menu_item_config(state.locale.tr("delete-session"))
+            .enabled(state.workspace.sessions.len() > 1)
+            .build()
+        {
+            state.remove_session();
+        }
+
+        ui.separator();
+        if ui.menu_item(state.locale.tr("recurring-expenses")) {
+            state.recurring_ui.open = true;
+        }
+        if ui.menu_item(state.locale.tr("totals-across-sessions")) {
+            state.workspace_ui.totals_open = true;
+        }
+        let save = Action::SaveWorkspace;
+        if ui
+            .menu_item_config(state.locale.tr(save.name()))
+            .shortcut(save.shortcut().map(|c| c.label()).unwrap_or_default())
+            .build()
+        {
+            save.run(state);
+        }
+    });
+}
+
+/// The active session's name, or what went wrong saving the workspace.
+pub fn status(ui: &imgui::Ui, state: &State) {
+    if let Some(message) = &state.workspace_ui.message {
+        ui.text_colored(state.settings.palette().error, message);
+    } else {
+        ui.text_disabled(&state.session_name);
+    }
+}
+
+/// Picks a contact who is not yet part of the current session.
+pub fn contacts_popup(ui: &imgui::Ui, state: &mut State) {
+    ui.popup("##contacts", || {
+        let available: Vec<Participant> = state
+            .workspace
+            .contacts
+            .iter()
+            .filter(|c| !state.participants.iter().any(|p| p.id == c.id))
+            .cloned()
+            .collect();
+
+        if available.is_empty() {
+            ui.text_disabled(state.locale.tr("everyone-is-here"));
+        }
+        for contact in available {
+            if ui.selectable(format!("{}##contact_{}", contact.name, contact.id)) {
+                state.add_participant(contact);
+            }
+        }
+    });
+}
+
+pub fn totals_window(ui: &imgui::Ui, state: &mut State) {
+    let mut open = state.workspace_ui.totals_open;
+
+    ui.window(format!(
+        "{}###totals",
+        state.locale.tr("totals-across-sessions")
+    ))
+    .size([480.0, 300.0], imgui::Condition::FirstUseEver)
+    .position([160., 100.], imgui::Condition::FirstUseEver)
+    .collapsible(false)
+    .opened(&mut open)
+    .build(|| {
+        let totals = totals(state);
+        let names: Vec<String> = state
+            .workspace
+            .sessions
+            .iter()
+            .enumerate()
+            .map(|(i, s)| {
+                if i == state.workspace.active {
+                    state.session_name.clone()
+                } else {
+                    s.name.clone()
+                }
+            })
+            .collect();
+
+        let columns = names.len() + 2;
+        let Some(_table) = ui.begin_table_with_flags(
+            "##totals",
+            columns,
+            imgui::TableFlags::BORDERS | imgui::TableFlags::SCROLL_X,
+        ) else {
+            return;
+        };
+
+        ui.table_setup_column(state.locale.tr("person"));
+        for name in names.iter() {
+            ui.table_setup_column(name);
+        }
+        ui.table_setup_column(state.locale.tr("total"));
+        ui.table_headers_row();
+
+        let mut people: Vec<(String, &Vec<i64>)> = totals
+            .iter()
+            .map(|(id, balances)| {
+                let name = state
+                    .participants
+                    .iter()
+                    .chain(state.workspace.contacts.iter())
+                    .find(|p| p.id == *id)
+                    .map_or(state.locale.tr("unknown"), |p| p.name.clone());
+                (name, balances)
+            })
+            .collect();
+        people.sort_by(|a, b| a.0.cmp(&b.0));
+
+        let palette = state.settings.palette();
+        let balance = |cents: i64| {
+            let text = state.settings.number(cents as f64 / 100.);
+            match palette.standing(cents) {
+                Some(color) => ui.text_colored(color, text),
+                None => ui.text(text),
+            }
+        };
+        for (name, balances) in people {
+            ui.table_next_row();
+            ui.table_next_column();
+            ui.text(name);
+            for cents in balances.iter() {
+                ui.table_next_column();
+                balance(*cents);
+            }
+            ui.table_next_column();
+            balance(balances.iter().sum());
+        }
+    });
+
+    state.workspace_ui.totals_open = open;
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn files_that_failed_to_load_are_backed_up() {
+        let workspace = Workspace::default();
+        let json = file_format::to_json(&workspace).unwrap();
+
+        let wrong_shape = r#"{ "version": 1, "workspace": { "sessions": "Flat 2B" } }"#;
+        assert!(file_format::from_json(wrong_shape).is_err());
+        assert_eq!(
+            files_to_save(Some(wrong_shape), &workspace).unwrap(),
+            [
+                (BACKUP_FILE, wrong_shape.to_string()),
+                (WORKSPACE_FILE, json.clone())
+            ]
+        );
+
+        for existing in [None, Some(json.as_str())] {
+            assert_eq!(
+                files_to_save(existing, &workspace).unwrap(),
+                [(WORKSPACE_FILE, json.clone())]
+            );
+        }
+        let newer = r#"{ "version": 1000, "workspace": {} }"#;
+        assert!(files_to_save(Some(newer), &workspace).is_err());
+    }
+}